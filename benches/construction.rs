@@ -0,0 +1,90 @@
+// Copyright (c) 2024-2025 Mikko Tanner. All rights reserved.
+// License: MIT OR Apache-2.0
+
+//! Cost of constructing a default hasher: building the [Xxh3] state with
+//! our secret on every call (what [CustomXxh3Hasher::default] does) versus
+//! cloning a pre-built template held in a process-wide [LazyLock] or in a
+//! per-thread [thread_local!].
+//!
+//! Run with `cargo bench --bench construction`.
+
+use custom_xxh3::{build_xxh3_with_custom_secret, CustomXxh3Hasher};
+use std::{
+    hash::Hasher,
+    hint::black_box,
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+use xxhash_rust::xxh3::Xxh3;
+
+/// Constructions per timing round.
+const BATCH: usize = 10_000;
+/// Minimum wall time per measurement.
+const MIN_TIME: Duration = Duration::from_millis(500);
+
+static TEMPLATE: LazyLock<Xxh3> = LazyLock::new(build_xxh3_with_custom_secret);
+
+thread_local! {
+    static THREAD_TEMPLATE: Xxh3 = build_xxh3_with_custom_secret();
+}
+
+/// Measure the mean cost of `f` in nanoseconds per call.
+fn measure<R>(f: impl Fn() -> R) -> f64 {
+    let start: Instant = Instant::now();
+    let mut calls: usize = 0;
+    while start.elapsed() < MIN_TIME {
+        for _ in 0..BATCH {
+            black_box(f());
+        }
+        calls += BATCH;
+    }
+    start.elapsed().as_nanos() as f64 / calls as f64
+}
+
+fn report(name: &str, ns: f64, baseline: f64) {
+    println!(
+        "{name:<28} {ns:>8.1} ns/call ({:+.1}%)",
+        (ns / baseline - 1.0) * 100.0
+    );
+}
+
+fn main() {
+    let built: f64 = measure(build_xxh3_with_custom_secret);
+    report("build Xxh3", built, built);
+    report(
+        "clone LazyLock template",
+        measure(|| TEMPLATE.clone()),
+        built,
+    );
+    report(
+        "clone thread-local template",
+        measure(|| THREAD_TEMPLATE.with(Xxh3::clone)),
+        built,
+    );
+
+    // construction plus a short write, as in a `build_hasher()` hot loop
+    let default: f64 = measure(|| {
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write_u64(black_box(42));
+        hasher.finish()
+    });
+    report("default() + write_u64", default, default);
+    report(
+        "template clone + write_u64",
+        measure(|| {
+            let mut xxh: Xxh3 = TEMPLATE.clone();
+            xxh.update(&black_box(42u64).to_ne_bytes());
+            xxh.digest()
+        }),
+        default,
+    );
+    report(
+        "thread-local + write_u64",
+        measure(|| {
+            let mut xxh: Xxh3 = THREAD_TEMPLATE.with(Xxh3::clone);
+            xxh.update(&black_box(42u64).to_ne_bytes());
+            xxh.digest()
+        }),
+        default,
+    );
+}
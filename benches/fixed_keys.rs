@@ -0,0 +1,79 @@
+// Copyright (c) 2024-2025 Mikko Tanner. All rights reserved.
+// License: MIT OR Apache-2.0
+
+//! Throughput of [hash_fixed_keys] against a blocked variant which issues
+//! software prefetches one block ahead, for a few key sizes and batch sizes.
+//!
+//! Run with `cargo bench --bench fixed_keys`.
+
+use custom_xxh3::{hash_bytes, hash_fixed_keys};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// Keys per block in the blocked variant.
+const BLOCK: usize = 64;
+/// Minimum wall time per measurement.
+const MIN_TIME: Duration = Duration::from_millis(500);
+
+/// Hint the CPU to pull the cache line holding `ptr` into all cache levels.
+#[inline(always)]
+fn prefetch<T>(ptr: *const T) {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+        _mm_prefetch(ptr as *const i8, _MM_HINT_T0);
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = ptr;
+}
+
+/// Hash the keys block by block, prefetching the next block beforehand.
+fn hash_fixed_keys_blocked<const N: usize>(keys: &[[u8; N]]) -> Vec<u64> {
+    let mut digests: Vec<u64> = Vec::with_capacity(keys.len());
+    for (idx, block) in keys.chunks(BLOCK).enumerate() {
+        if let Some(next) = keys.get((idx + 1) * BLOCK..) {
+            for key in next.iter().take(BLOCK).step_by((64 / N).max(1)) {
+                prefetch(key);
+            }
+        }
+        digests.extend(block.iter().map(|key| hash_bytes(key)));
+    }
+    digests
+}
+
+/// Measure the throughput of `f` over `keys` in millions of keys per second.
+fn measure<const N: usize>(keys: &[[u8; N]], f: fn(&[[u8; N]]) -> Vec<u64>) -> f64 {
+    let start: Instant = Instant::now();
+    let mut rounds: usize = 0;
+    while start.elapsed() < MIN_TIME {
+        black_box(f(black_box(keys)));
+        rounds += 1;
+    }
+    (rounds * keys.len()) as f64 / start.elapsed().as_secs_f64() / 1e6
+}
+
+fn bench<const N: usize>(count: usize) {
+    let keys: Vec<[u8; N]> = (0..count)
+        .map(|i| std::array::from_fn(|j| (i * 31 + j) as u8))
+        .collect();
+    assert_eq!(hash_fixed_keys(&keys), hash_fixed_keys_blocked(&keys));
+
+    let plain: f64 = measure(&keys, hash_fixed_keys::<N>);
+    let blocked: f64 = measure(&keys, hash_fixed_keys_blocked::<N>);
+    println!(
+        "{N:>3} B x {count:>9}: plain {plain:>7.1} Mkeys/s ({:>6.0} MB/s), blocked+prefetch {blocked:>7.1} Mkeys/s ({:+.1}%)",
+        plain * N as f64,
+        (blocked / plain - 1.0) * 100.0
+    );
+}
+
+fn main() {
+    for count in [1_000, 100_000, 4_000_000] {
+        bench::<8>(count);
+        bench::<16>(count);
+        bench::<32>(count);
+        bench::<64>(count);
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright (c) 2024-2025 Mikko Tanner. All rights reserved.
+// License: MIT OR Apache-2.0
+
+//! Throughput of the oneshot [hash_bytes] path against a streaming default
+//! [CustomXxh3Hasher] fed the whole input in one write, per input size.
+//! Both produce identical digests; this is for finding the size (if any)
+//! from which streaming is faster.
+//!
+//! Run with `cargo bench --bench oneshot_streaming`.
+
+use custom_xxh3::{hash_bytes, CustomXxh3Hasher};
+use std::{
+    hash::Hasher,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// Minimum wall time per measurement.
+const MIN_TIME: Duration = Duration::from_millis(300);
+
+/// Hash the input with a streaming default hasher.
+fn hash_streaming(bytes: &[u8]) -> u64 {
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+/// Measure the throughput of `f` over `data` in MB/s.
+fn measure(data: &[u8], f: fn(&[u8]) -> u64) -> f64 {
+    let start: Instant = Instant::now();
+    let mut rounds: usize = 0;
+    while start.elapsed() < MIN_TIME {
+        black_box(f(black_box(data)));
+        rounds += 1;
+    }
+    (rounds * data.len()) as f64 / start.elapsed().as_secs_f64() / 1e6
+}
+
+fn main() {
+    let data: Vec<u8> = (0..64 << 20).map(|i: usize| (i % 251) as u8).collect();
+    for shift in (4..=26).step_by(2) {
+        let input: &[u8] = &data[..1 << shift];
+        assert_eq!(hash_bytes(input), hash_streaming(input));
+
+        let oneshot: f64 = measure(input, hash_bytes);
+        let streaming: f64 = measure(input, hash_streaming);
+        println!(
+            "{:>9} B: oneshot {oneshot:>8.0} MB/s, streaming {streaming:>8.0} MB/s ({:+.1}%)",
+            input.len(),
+            (streaming / oneshot - 1.0) * 100.0
+        );
+    }
+}
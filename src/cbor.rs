@@ -0,0 +1,226 @@
+// Copyright (c) 2024-2025 Mikko Tanner. All rights reserved.
+// License: MIT OR Apache-2.0
+
+use crate::{hash_bytes, Xxh3Error};
+use ciborium::Value;
+
+/// CBOR major types (the top 3 bits of an item's initial byte).
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+/// Write an item head with the shortest possible argument encoding.
+fn write_head(out: &mut Vec<u8>, major: u8, arg: u64) {
+    let major: u8 = major << 5;
+    match arg {
+        0..=23 => out.push(major | arg as u8),
+        24..=0xFF => out.extend_from_slice(&[major | 24, arg as u8]),
+        0x100..=0xFFFF => {
+            out.push(major | 25);
+            out.extend_from_slice(&(arg as u16).to_be_bytes());
+        }
+        0x1_0000..=0xFFFF_FFFF => {
+            out.push(major | 26);
+            out.extend_from_slice(&(arg as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&arg.to_be_bytes());
+        }
+    }
+}
+
+/// Convert a float to IEEE 754 half precision bits, if that is lossless.
+fn f64_to_f16_exact(v: f64) -> Option<u16> {
+    let single: f32 = v as f32;
+    if single as f64 != v {
+        return None;
+    }
+    let bits: u32 = single.to_bits();
+    let sign: u16 = ((bits >> 16) & 0x8000) as u16;
+    let exp: i32 = ((bits >> 23) & 0xFF) as i32 - 127;
+    let mantissa: u32 = bits & 0x7F_FFFF;
+    match exp {
+        -127 if mantissa == 0 => Some(sign),
+        128 if mantissa == 0 => Some(sign | 0x7C00),
+        -14..=15 if mantissa & 0x1FFF == 0 => {
+            Some(sign | (((exp + 15) as u16) << 10) | (mantissa >> 13) as u16)
+        }
+        -24..=-15 => {
+            let shift: u32 = (-(exp + 1)) as u32;
+            let significand: u32 = mantissa | 0x80_0000;
+            (significand & ((1 << shift) - 1) == 0).then_some(sign | (significand >> shift) as u16)
+        }
+        _ => None,
+    }
+}
+
+/// Write a float in its shortest lossless form (half, single or double
+/// precision), with NaN canonicalized to the half precision quiet NaN.
+fn write_float(out: &mut Vec<u8>, v: f64) {
+    let simple: u8 = MAJOR_SIMPLE << 5;
+    if v.is_nan() {
+        out.extend_from_slice(&[simple | 25, 0x7E, 0x00]);
+    } else if let Some(half) = f64_to_f16_exact(v) {
+        out.push(simple | 25);
+        out.extend_from_slice(&half.to_be_bytes());
+    } else if (v as f32) as f64 == v {
+        out.push(simple | 26);
+        out.extend_from_slice(&(v as f32).to_be_bytes());
+    } else {
+        out.push(simple | 27);
+        out.extend_from_slice(&v.to_be_bytes());
+    }
+}
+
+/// Append the canonical CBOR encoding of a value.
+fn encode_canonical(out: &mut Vec<u8>, value: &Value) -> Result<(), Xxh3Error> {
+    match value {
+        Value::Integer(int) => {
+            let n: i128 = (*int).into();
+            match n {
+                0.. => write_head(out, MAJOR_UNSIGNED, n as u64),
+                _ => write_head(out, MAJOR_NEGATIVE, (-1 - n) as u64),
+            }
+        }
+        Value::Bytes(bytes) => {
+            write_head(out, MAJOR_BYTES, bytes.len() as u64);
+            out.extend_from_slice(bytes);
+        }
+        Value::Text(text) => {
+            write_head(out, MAJOR_TEXT, text.len() as u64);
+            out.extend_from_slice(text.as_bytes());
+        }
+        Value::Array(items) => {
+            write_head(out, MAJOR_ARRAY, items.len() as u64);
+            for item in items {
+                encode_canonical(out, item)?;
+            }
+        }
+        Value::Map(entries) => {
+            let mut encoded: Vec<(Vec<u8>, Vec<u8>)> = entries
+                .iter()
+                .map(|(k, v)| Ok((encode_value(k)?, encode_value(v)?)))
+                .collect::<Result<_, Xxh3Error>>()?;
+            encoded.sort_unstable();
+            write_head(out, MAJOR_MAP, encoded.len() as u64);
+            for (k, v) in encoded {
+                out.extend_from_slice(&k);
+                out.extend_from_slice(&v);
+            }
+        }
+        Value::Tag(tag, inner) => {
+            write_head(out, MAJOR_TAG, *tag);
+            encode_canonical(out, inner)?;
+        }
+        Value::Float(v) => write_float(out, *v),
+        Value::Bool(false) => out.push(MAJOR_SIMPLE << 5 | 20),
+        Value::Bool(true) => out.push(MAJOR_SIMPLE << 5 | 21),
+        Value::Null => out.push(MAJOR_SIMPLE << 5 | 22),
+        _ => return Err(Xxh3Error::UnsupportedValue),
+    }
+    Ok(())
+}
+
+/// Get the canonical CBOR encoding of a value.
+fn encode_value(value: &Value) -> Result<Vec<u8>, Xxh3Error> {
+    let mut out: Vec<u8> = Vec::new();
+    encode_canonical(&mut out, value)?;
+    Ok(out)
+}
+
+/**
+Hash a CBOR value by the [hash_bytes] digest of its canonical encoding,
+for language-agnostic structured content digests.
+
+The encoding follows the core deterministic encoding of RFC 8949 §4.2.1:
+definite lengths, shortest-form integer and length arguments, shortest
+lossless floats (NaN as `0xF97E00`), and map entries sorted by the bytewise
+order of their encoded keys. Semantically equal values thus hash equally
+regardless of e.g. map key order, and any implementation producing the
+same encoding yields the same digest.
+
+Returns [Xxh3Error::UnsupportedValue] if the value contains a `Value`
+variant added to `ciborium` after this was written, as its canonical
+encoding is unknown.
+*/
+pub fn hash_cbor(value: &Value) -> Result<u64, Xxh3Error> {
+    Ok(hash_bytes(&encode_value(value)?))
+}
+
+/* ######################################################################### */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(value: &Value) -> Vec<u8> {
+        encode_value(value).unwrap()
+    }
+
+    fn text(s: &str) -> Value {
+        Value::Text(s.into())
+    }
+
+    #[test]
+    fn test_canonical_encoding() {
+        let int = |n: i64| encode(&Value::Integer(n.into()));
+
+        assert_eq!(int(0), [0x00]);
+        assert_eq!(int(23), [0x17]);
+        assert_eq!(int(24), [0x18, 0x18]);
+        assert_eq!(int(1000), [0x19, 0x03, 0xE8]);
+        assert_eq!(int(-1), [0x20]);
+        assert_eq!(int(-1000), [0x39, 0x03, 0xE7]);
+        assert_eq!(encode(&Value::Float(1.5)), [0xF9, 0x3E, 0x00]);
+        assert_eq!(
+            encode(&Value::Float(5.960464477539063e-8)),
+            [0xF9, 0x00, 0x01]
+        );
+        assert_eq!(
+            encode(&Value::Float(100000.0)),
+            [0xFA, 0x47, 0xC3, 0x50, 0x00]
+        );
+        assert_eq!(encode(&Value::Float(1.1))[0], 0xFB);
+        assert_eq!(encode(&Value::Float(f64::NAN)), [0xF9, 0x7E, 0x00]);
+        assert_eq!(encode(&Value::Float(f64::NEG_INFINITY)), [0xF9, 0xFC, 0x00]);
+        assert_eq!(encode(&text("a")), [0x61, b'a']);
+        assert_eq!(encode(&Value::Tag(1, Box::new(Value::Null))), [0xC1, 0xF6]);
+    }
+
+    #[test]
+    fn test_hash_cbor_map_order() {
+        let map1 = Value::Map(vec![
+            (text("b"), Value::Integer(2.into())),
+            (
+                text("a"),
+                Value::Array(vec![Value::Bool(true), Value::Null]),
+            ),
+            (Value::Integer(10.into()), Value::Bytes(vec![1, 2, 3])),
+        ]);
+        let map2 = Value::Map(vec![
+            (Value::Integer(10.into()), Value::Bytes(vec![1, 2, 3])),
+            (
+                text("a"),
+                Value::Array(vec![Value::Bool(true), Value::Null]),
+            ),
+            (text("b"), Value::Integer(2.into())),
+        ]);
+
+        assert_eq!(hash_cbor(&map1).unwrap(), hash_cbor(&map2).unwrap());
+        assert_eq!(
+            encode(&map1)[..4],
+            [0xA3, 0x0A, 0x43, 0x01],
+            "Shorter encoded keys should sort first"
+        );
+        assert_ne!(
+            hash_cbor(&map1).unwrap(),
+            hash_cbor(&Value::Map(vec![(text("b"), Value::Integer(3.into()))])).unwrap()
+        );
+    }
+}
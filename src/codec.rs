@@ -0,0 +1,200 @@
+// Copyright (c) 2024-2025 Mikko Tanner. All rights reserved.
+// License: MIT OR Apache-2.0
+
+use crate::{hash_bytes_128, Xxh3Error};
+
+/// Crockford's base32 alphabet (no `I`, `L`, `O` or `U`).
+const CROCKFORD_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Number of Crockford base32 characters needed for a 128-bit value.
+const CROCKFORD_U128_LEN: usize = 26;
+
+/// Encode a `u128` as 26 Crockford base32 characters, most significant
+/// bits first (the first character only carries the top 3 bits).
+fn encode_crockford_u128(value: u128) -> String {
+    (0..CROCKFORD_U128_LEN)
+        .rev()
+        .map(|i| CROCKFORD_ALPHABET[((value >> (5 * i)) & 0x1F) as usize] as char)
+        .collect()
+}
+
+/**
+Compute a compact, human-shareable content id for a byte slice.
+
+The id is the [hash_bytes_128] digest encoded as Crockford base32: 26
+upper-case characters, no padding. Crockford base32 decodes case-insensitively
+and avoids ambiguous letters, which makes the ids suitable for URLs and file names.
+*/
+pub fn content_id(bytes: &[u8]) -> String {
+    encode_crockford_u128(hash_bytes_128(bytes))
+}
+
+/* --------------------------------- */
+
+/// The URL- and filename-safe base64 alphabet (RFC 4648 §5).
+const BASE64URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encode bytes as unpadded base64url.
+fn encode_base64url(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    let (mut acc, mut bits) = (0u32, 0u32);
+    for &b in bytes {
+        acc = (acc << 8) | b as u32;
+        bits += 8;
+        while bits >= 6 {
+            bits -= 6;
+            out.push(BASE64URL_ALPHABET[((acc >> bits) & 0x3F) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE64URL_ALPHABET[((acc << (6 - bits)) & 0x3F) as usize] as char);
+    }
+    out
+}
+
+/// Decode exactly `N` bytes from unpadded base64url. Only the canonical
+/// encoding is accepted: the length must match and unused bits be zero.
+fn decode_base64url<const N: usize>(s: &str) -> Result<[u8; N], Xxh3Error> {
+    if s.len() != (N * 4).div_ceil(3) {
+        return Err(Xxh3Error::InvalidEncoding);
+    }
+    let mut out = [0u8; N];
+    let (mut acc, mut bits, mut i) = (0u32, 0u32, 0usize);
+    for c in s.bytes() {
+        let v = BASE64URL_ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or(Xxh3Error::InvalidEncoding)?;
+        acc = (acc << 6) | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out[i] = (acc >> bits) as u8;
+            i += 1;
+        }
+    }
+    if acc & ((1 << bits) - 1) != 0 {
+        return Err(Xxh3Error::InvalidEncoding);
+    }
+    Ok(out)
+}
+
+/// Encode a 64-bit digest as 11 unpadded base64url characters (of its
+/// big-endian bytes), for compactly embedding digests in URLs.
+pub fn digest_to_base64url(d: u64) -> String {
+    encode_base64url(&d.to_be_bytes())
+}
+
+/// Decode a 64-bit digest encoded with [digest_to_base64url].
+pub fn digest_from_base64url(s: &str) -> Result<u64, Xxh3Error> {
+    decode_base64url(s).map(u64::from_be_bytes)
+}
+
+/// Encode a 128-bit digest as 22 unpadded base64url characters (of its
+/// big-endian bytes).
+pub fn digest128_to_base64url(d: u128) -> String {
+    encode_base64url(&d.to_be_bytes())
+}
+
+/// Decode a 128-bit digest encoded with [digest128_to_base64url].
+pub fn digest128_from_base64url(s: &str) -> Result<u128, Xxh3Error> {
+    decode_base64url(s).map(u128::from_be_bytes)
+}
+
+/* --------------------------------- */
+
+/// Palette of 16 colored glyphs for [fingerprint_glyphs], indexed by nibble.
+const GLYPH_PALETTE: [char; 16] = [
+    '🟥', '🟧', '🟨', '🟩', '🟦', '🟪', '🟫', '⬛', '⬜', '🔴', '🟠', '🟡', '🟢', '🔵', '🟣', '🟤',
+];
+
+/**
+Render a digest as a short sequence of colored glyphs, so humans can
+eyeball whether two digests match (e.g. in CLI output).
+
+Every glyph encodes one 4-bit nibble of the digest, most significant first,
+as an index into a fixed palette of 16 colored squares and circles
+(`0x0` = 🟥 ... `0xF` = 🟤). At most 16 glyphs (all 64 bits) are produced;
+fewer glyphs only cover the leading bits of the digest.
+*/
+pub fn fingerprint_glyphs(d: u64, n: usize) -> String {
+    (0..n.min(16))
+        .map(|i| GLYPH_PALETTE[((d >> (60 - 4 * i)) & 0xF) as usize])
+        .collect()
+}
+
+/* ######################################################################### */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crockford_encoding() {
+        assert_eq!(encode_crockford_u128(0), "0".repeat(26));
+        assert_eq!(
+            encode_crockford_u128(u128::MAX),
+            format!("7{}", "Z".repeat(25))
+        );
+        assert_eq!(encode_crockford_u128(32), format!("{}10", "0".repeat(24)));
+    }
+
+    #[test]
+    fn test_content_id() {
+        let id = content_id(b"Hello, world!");
+
+        assert_eq!(id.len(), CROCKFORD_U128_LEN);
+        assert!(id.bytes().all(|c| CROCKFORD_ALPHABET.contains(&c)));
+        assert_eq!(id, content_id(b"Hello, world!"));
+        assert_eq!(id, "72SKARMC5R8FH0K6Z060YSBBM1");
+    }
+
+    #[test]
+    fn test_base64url_roundtrip() {
+        for d in [0, 1, 0xFB, u64::MAX, 0x0123_4567_89AB_CDEF] {
+            let s = digest_to_base64url(d);
+            assert_eq!(s.len(), 11);
+            assert_eq!(digest_from_base64url(&s).unwrap(), d);
+        }
+        for d in [0, u128::MAX, hash_bytes_128(b"Hello, world!")] {
+            let s = digest128_to_base64url(d);
+            assert_eq!(s.len(), 22);
+            assert_eq!(digest128_from_base64url(&s).unwrap(), d);
+        }
+
+        assert_eq!(digest_to_base64url(0), "AAAAAAAAAAA");
+        assert_eq!(digest_to_base64url(u64::MAX), "__________8");
+        assert_eq!(digest_to_base64url(0xFB), "AAAAAAAAAPs");
+    }
+
+    #[test]
+    fn test_base64url_malformed() {
+        let bad = [
+            "",
+            "AAAAAAAAAA",
+            "AAAAAAAAAAAA",
+            "AAAAAAAAAA=",
+            "AAAAAAAAAA+",
+            "AAAAAAAAAAB",
+        ];
+        for s in bad {
+            assert!(matches!(
+                digest_from_base64url(s),
+                Err(Xxh3Error::InvalidEncoding)
+            ));
+        }
+        assert!(digest128_from_base64url(&digest_to_base64url(1)).is_err());
+    }
+
+    #[test]
+    fn test_fingerprint_glyphs() {
+        let d = crate::hash_bytes(b"Hello, world!");
+
+        assert_eq!(fingerprint_glyphs(d, 6), fingerprint_glyphs(d, 6));
+        assert_ne!(fingerprint_glyphs(d, 6), fingerprint_glyphs(!d, 6));
+        assert_eq!(fingerprint_glyphs(d, 100).chars().count(), 16);
+        assert_eq!(fingerprint_glyphs(0x0F00_0000_0000_0000, 3), "🟥🟤🟥");
+        assert!(fingerprint_glyphs(d, 0).is_empty());
+    }
+}
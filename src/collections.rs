@@ -0,0 +1,343 @@
+// Copyright (c) 2024-2025 Mikko Tanner. All rights reserved.
+// License: MIT OR Apache-2.0
+
+use crate::{hash_bytes_128, hash_item, hash_iter, CustomXxh3Hasher};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
+
+/// Hash a single map entry with a fresh default [CustomXxh3Hasher].
+#[inline]
+fn entry_digest<K: Hash, V: Hash>(key: &K, value: &V) -> u64 {
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    key.hash(&mut hasher);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/**
+Compute an order-independent digest of a [HashMap]'s entries.
+
+Each entry is hashed separately and the entry digests are XOR-combined, so
+the result doesn't depend on iteration order (nor on the map's own hasher).
+The combined value is finally hashed together with the entry count.
+*/
+pub fn hash_map_unordered<K: Hash, V: Hash, S>(map: &HashMap<K, V, S>) -> u64 {
+    let combined: u64 = map
+        .iter()
+        .fold(0, |acc, (key, value)| acc ^ entry_digest(key, value));
+    finish_unordered(combined, map.len() as u64)
+}
+
+/// Hash an XOR-combined digest together with the number of combined items.
+#[inline]
+fn finish_unordered(combined: u64, count: u64) -> u64 {
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    hasher.write(&count.to_le_bytes());
+    hasher.write(&combined.to_le_bytes());
+    hasher.finish()
+}
+
+/// Compute a digest of a [BTreeMap]'s entries in their sorted order,
+/// prefixed with the entry count.
+pub fn hash_btreemap_ordered<K: Hash, V: Hash>(map: &BTreeMap<K, V>) -> u64 {
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    hasher.write(&(map.len() as u64).to_le_bytes());
+    for (key, value) in map {
+        key.hash(&mut hasher);
+        value.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/**
+Hash the items of any collection, either in order or order-independently.
+
+With `ordered` set, the items are streamed in iteration order (as with
+[hash_iter]), so e.g. reordering a `Vec` changes the digest. Otherwise each
+item is hashed separately and the item digests are XOR-combined, which
+suits collections without a stable order, such as a `HashSet`.
+
+NOTE: the unordered path has weaker collision properties: any item present
+an even number of times cancels out, so multisets differing only by such
+pairs collide (the item count is mixed in, which catches some but not all
+of these). Use it for sets, not for collections with duplicates.
+*/
+pub fn hash_collection<I, T>(items: I, ordered: bool) -> u64
+where
+    I: IntoIterator<Item = T>,
+    T: Hash,
+{
+    if ordered {
+        return hash_iter(items);
+    }
+    let (combined, count) = items.into_iter().fold((0u64, 0u64), |(acc, n), item| {
+        (acc ^ hash_item(&item), n + 1)
+    });
+    finish_unordered(combined, count)
+}
+
+/**
+Compute a stable fingerprint of a key-value config (e.g. environment
+variables), for cache invalidation.
+
+The pairs are sorted by key (and by value for repeated keys), so the input
+order doesn't matter, but every key and value does. The pair count is
+written first as a little-endian `u64`, then every key and value framed
+with its length, as by [write_framed](CustomXxh3Hasher::write_framed), so
+e.g. `("a=b", "c")` and `("a", "b=c")` don't collide.
+*/
+pub fn hash_config<I: IntoIterator<Item = (String, String)>>(pairs: I) -> u64 {
+    let mut pairs: Vec<(String, String)> = pairs.into_iter().collect();
+    pairs.sort_unstable();
+
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    hasher.write(&(pairs.len() as u64).to_le_bytes());
+    for (key, value) in &pairs {
+        hasher.write_framed(key.as_bytes());
+        hasher.write_framed(value.as_bytes());
+    }
+    hasher.finish()
+}
+
+/**
+Hash a 2D grid (e.g. a matrix or image) of byte rows, preserving its shape.
+
+The row count is written first as a little-endian `u64`, followed by every
+row framed with its length (little-endian `u64`) as by
+[write_framed](CustomXxh3Hasher::write_framed). Transposing or reshaping
+the grid thus changes the digest even if the concatenated bytes don't.
+*/
+pub fn hash_grid(rows: &[&[u8]]) -> u64 {
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    hasher.write(&(rows.len() as u64).to_le_bytes());
+    for row in rows {
+        hasher.write_framed(row);
+    }
+    hasher.finish()
+}
+
+/* --------------------------------- */
+
+/**
+A dedup filter for large data streams, storing only the [hash_bytes_128]
+digests of inserted byte slices (16 bytes each) instead of the data.
+
+There are no false negatives: data inserted before is always reported as
+seen. Since the data isn't kept, a digest collision would make distinct
+data look like a duplicate (a false positive), but with 128 bits the odds
+are negligible (see [collision_probability](crate::collision_probability)).
+*/
+#[derive(Clone, Debug, Default)]
+pub struct Xxh3DedupSet {
+    digests: HashSet<u128>,
+}
+
+impl Xxh3DedupSet {
+    /// Create a new, empty dedup set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new, empty dedup set with room for `capacity` digests.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            digests: HashSet::with_capacity(capacity),
+        }
+    }
+
+    /// Insert data, returning `true` if it was not seen before.
+    #[inline]
+    pub fn insert(&mut self, bytes: &[u8]) -> bool {
+        self.digests.insert(hash_bytes_128(bytes))
+    }
+
+    /// Check whether data has been seen before, without inserting it.
+    #[inline]
+    pub fn contains(&self, bytes: &[u8]) -> bool {
+        self.digests.contains(&hash_bytes_128(bytes))
+    }
+
+    /// Get the number of distinct digests seen.
+    pub fn len(&self) -> usize {
+        self.digests.len()
+    }
+
+    /// Check whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.digests.is_empty()
+    }
+}
+
+/* --------------------------------- */
+
+/// Generate `hash_tupleN` functions for tuples of fixed arity.
+macro_rules! hash_tuple_fns {
+    ($($name:ident: $($idx:tt $t:ident),+;)+) => {$(
+        /// Compute a stable digest of a tuple. Each element is hashed into
+        /// its own digest, and the element digests are written in order as
+        /// little-endian `u64`s, so element boundaries can't shift.
+        pub fn $name<$($t: Hash),+>(tuple: &($($t,)+)) -> u64 {
+            let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+            $(hasher.write(&hash_item(&tuple.$idx).to_le_bytes());)+
+            hasher.finish()
+        }
+    )+};
+}
+
+hash_tuple_fns! {
+    hash_tuple2: 0 A, 1 B;
+    hash_tuple3: 0 A, 1 B, 2 C;
+    hash_tuple4: 0 A, 1 B, 2 C, 3 D;
+    hash_tuple5: 0 A, 1 B, 2 C, 3 D, 4 E;
+    hash_tuple6: 0 A, 1 B, 2 C, 3 D, 4 E, 5 F;
+}
+
+/* ######################################################################### */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_map_order_independence() {
+        let mut map1: HashMap<String, u32> = HashMap::new();
+        let mut map2: HashMap<String, u32> = HashMap::with_capacity(1024);
+        for i in 0..100 {
+            map1.insert(format!("key-{i}"), i);
+        }
+        for i in (0..100).rev() {
+            map2.insert(format!("key-{i}"), i);
+        }
+
+        assert_eq!(hash_map_unordered(&map1), hash_map_unordered(&map2));
+
+        map2.insert("key-0".to_string(), 1000);
+        assert_ne!(hash_map_unordered(&map1), hash_map_unordered(&map2));
+    }
+
+    #[test]
+    fn test_hash_btreemap_determinism() {
+        let map1: BTreeMap<&str, u32> = [("a", 1), ("b", 2), ("c", 3)].into_iter().collect();
+        let map2: BTreeMap<&str, u32> = [("c", 3), ("a", 1), ("b", 2)].into_iter().collect();
+        let map3: BTreeMap<&str, u32> = [("a", 1), ("b", 2), ("c", 4)].into_iter().collect();
+
+        assert_eq!(hash_btreemap_ordered(&map1), hash_btreemap_ordered(&map2));
+        assert_ne!(hash_btreemap_ordered(&map1), hash_btreemap_ordered(&map3));
+    }
+
+    /// Hashes its bytes without any framing of its own.
+    struct Raw(&'static [u8]);
+
+    impl Hash for Raw {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            state.write(self.0);
+        }
+    }
+
+    #[test]
+    fn test_hash_tuple2_framing() {
+        assert_ne!(
+            hash_tuple2(&(Raw(b"ab"), Raw(b"c"))),
+            hash_tuple2(&(Raw(b"a"), Raw(b"bc")))
+        );
+        assert_eq!(hash_tuple2(&("ab", 1u8)), hash_tuple2(&("ab", 1u8)));
+    }
+
+    #[test]
+    fn test_hash_tuple3_framing() {
+        let digest = hash_tuple3(&(Raw(b"a"), Raw(b"b"), Raw(b"c")));
+
+        assert_ne!(digest, hash_tuple3(&(Raw(b"ab"), Raw(b""), Raw(b"c"))));
+        assert_ne!(digest, hash_tuple3(&(Raw(b""), Raw(b"ab"), Raw(b"c"))));
+        assert_ne!(digest, hash_tuple3(&(Raw(b"c"), Raw(b"b"), Raw(b"a"))));
+        assert_eq!(digest, hash_tuple3(&(Raw(b"a"), Raw(b"b"), Raw(b"c"))));
+    }
+
+    #[test]
+    fn test_hash_collection_ordered() {
+        let items = vec![1u32, 2, 3];
+
+        assert_eq!(hash_collection(&items, true), hash_iter(&items));
+        assert_eq!(
+            hash_collection(&items, true),
+            hash_collection(vec![1u32, 2, 3], true)
+        );
+        assert_ne!(
+            hash_collection(&items, true),
+            hash_collection(vec![3u32, 2, 1], true)
+        );
+    }
+
+    #[test]
+    fn test_hash_collection_unordered() {
+        let set1: std::collections::HashSet<u32> = (0..100).collect();
+        let set2: std::collections::HashSet<u32> = (0..100).rev().collect();
+
+        assert_eq!(hash_collection(&set1, false), hash_collection(&set2, false));
+        assert_eq!(
+            hash_collection([1u32, 2, 3], false),
+            hash_collection([3u32, 2, 1], false)
+        );
+        assert_ne!(
+            hash_collection([1u32, 2, 3], false),
+            hash_collection([1u32, 2, 4], false)
+        );
+        assert_ne!(
+            hash_collection([1u32, 2, 3], false),
+            hash_collection([1u32, 2, 3], true)
+        );
+    }
+
+    #[test]
+    fn test_hash_grid() {
+        let grid: [&[u8]; 2] = [b"abc", b"def"];
+        let reshaped: [&[u8]; 3] = [b"ab", b"cd", b"ef"];
+        let shifted: [&[u8]; 2] = [b"ab", b"cdef"];
+
+        assert_eq!(hash_grid(&grid), hash_grid(&[b"abc", b"def"]));
+        assert_ne!(hash_grid(&grid), hash_grid(&reshaped));
+        assert_ne!(hash_grid(&grid), hash_grid(&shifted));
+        assert_ne!(hash_grid(&[]), hash_grid(&[b""]));
+    }
+
+    #[test]
+    fn test_dedup_set() {
+        let mut set = Xxh3DedupSet::new();
+
+        assert!(set.insert(b"foo"));
+        assert!(set.insert(b"bar"));
+        assert!(!set.insert(b"foo"), "Duplicate should be detected");
+        assert!(set.contains(b"bar"));
+        assert!(!set.contains(b"baz"));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_hash_config() {
+        let pairs = |list: &[(&str, &str)]| -> Vec<(String, String)> {
+            list.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let config = hash_config(pairs(&[("HOME", "/root"), ("PATH", "/bin"), ("LANG", "C")]));
+
+        assert_eq!(
+            config,
+            hash_config(pairs(&[("LANG", "C"), ("HOME", "/root"), ("PATH", "/bin")]))
+        );
+        assert_ne!(
+            config,
+            hash_config(pairs(&[
+                ("HOME", "/root"),
+                ("PATH", "/usr/bin"),
+                ("LANG", "C")
+            ]))
+        );
+        assert_ne!(
+            hash_config(pairs(&[("a=b", "c")])),
+            hash_config(pairs(&[("a", "b=c")]))
+        );
+    }
+}
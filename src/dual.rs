@@ -0,0 +1,77 @@
+// Copyright (c) 2024-2025 Mikko Tanner. All rights reserved.
+// License: MIT OR Apache-2.0
+
+use crate::CustomXxh3Hasher;
+use std::hash::Hasher;
+
+/**
+A [Hasher] computing both an `xxHash3` digest and a legacy CRC32 checksum
+of the same stream in a single pass, e.g. during a migration period.
+
+[finish](Hasher::finish) returns the `xxHash3` digest.
+*/
+#[derive(Clone, Debug, Default)]
+pub struct DualChecksum {
+    xxh3: CustomXxh3Hasher,
+    crc32: crc32fast::Hasher,
+}
+
+impl DualChecksum {
+    /// Create a new [DualChecksum] with a default [CustomXxh3Hasher].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new [DualChecksum] around a given [CustomXxh3Hasher].
+    pub fn with_hasher(xxh3: CustomXxh3Hasher) -> Self {
+        Self {
+            xxh3,
+            crc32: crc32fast::Hasher::new(),
+        }
+    }
+
+    /// Get the `xxHash3` digest of the data written so far.
+    #[inline]
+    pub fn xxh3(&self) -> u64 {
+        self.xxh3.finish()
+    }
+
+    /// Get the CRC32 checksum of the data written so far.
+    #[inline]
+    pub fn crc32(&self) -> u32 {
+        self.crc32.clone().finalize()
+    }
+}
+
+impl Hasher for DualChecksum {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.xxh3.write(bytes);
+        self.crc32.update(bytes);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.xxh3()
+    }
+}
+
+/* ######################################################################### */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash_bytes;
+
+    #[test]
+    fn test_dual_checksum() {
+        let mut dual = DualChecksum::new();
+        dual.write(b"1234");
+        dual.write(b"56789");
+
+        assert_eq!(dual.crc32(), 0xCBF4_3926);
+        assert_eq!(dual.crc32(), crc32fast::hash(b"123456789"));
+        assert_eq!(dual.xxh3(), hash_bytes(b"123456789"));
+        assert_eq!(dual.finish(), dual.xxh3());
+    }
+}
@@ -0,0 +1,449 @@
+// Copyright (c) 2024-2025 Mikko Tanner. All rights reserved.
+// License: MIT OR Apache-2.0
+
+use crate::{hash_bytes, CustomXxh3Hasher};
+use std::{
+    hash::Hasher,
+    io::{self, Read, Write},
+};
+
+/// Size of the checksum trailer written by [write_with_checksum].
+const CHECKSUM_TRAILER_SIZE: usize = 8;
+/// Size of the stack buffer used when streaming from readers.
+const READ_BUF_SIZE: usize = 8192;
+
+/// Stream everything from `reader` into `hasher`, returning the number of
+/// bytes consumed.
+fn stream_into<R: Read>(hasher: &mut CustomXxh3Hasher, reader: &mut R) -> io::Result<u64> {
+    let mut buf = [0u8; READ_BUF_SIZE];
+    let mut total: u64 = 0;
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(total),
+            Ok(n) => {
+                hasher.write(&buf[..n]);
+                total += n as u64;
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+impl CustomXxh3Hasher {
+    /**
+    Stream at most `max_bytes` from `reader` into the hasher, returning the
+    number of bytes consumed.
+
+    Returns an [io::ErrorKind::InvalidData] error if the reader holds more
+    than `max_bytes`. In that case only the first `max_bytes` are hashed,
+    and the hasher should be [reset](CustomXxh3Hasher::reset) before reuse.
+    */
+    pub fn ingest<R: Read>(&mut self, reader: &mut R, max_bytes: u64) -> io::Result<u64> {
+        let mut buf = [0u8; READ_BUF_SIZE];
+        let mut limited = reader.take(max_bytes.saturating_add(1));
+        let mut total: u64 = 0;
+        loop {
+            let n: usize = match limited.read(&mut buf) {
+                Ok(0) => return Ok(total),
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if total + n as u64 > max_bytes {
+                self.write(&buf[..(max_bytes - total) as usize]);
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("input exceeds the limit of {max_bytes} bytes"),
+                ));
+            }
+            self.write(&buf[..n]);
+            total += n as u64;
+        }
+    }
+}
+
+/**
+Hash untrusted input from `reader` with a default [CustomXxh3Hasher], reading
+at most `max + 1` bytes. Returns `Some(digest)` if the input fits in `max`
+bytes, or `None` if it exceeds the limit (the hash is aborted), which
+protects e.g. servers from endless streams.
+*/
+pub fn hash_reader_bounded<R: Read>(reader: &mut R, max: u64) -> io::Result<Option<u64>> {
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    let total: u64 = stream_into(&mut hasher, &mut reader.take(max.saturating_add(1)))?;
+    Ok((total <= max).then(|| hasher.finish()))
+}
+
+/**
+Hash several readers as one logical stream: each reader is streamed to its
+end, in order, through a single default [CustomXxh3Hasher], so the digest
+equals [hash_bytes] of their concatenation. Empty readers contribute
+nothing.
+*/
+pub fn hash_readers<R: Read>(readers: &mut [R]) -> io::Result<u64> {
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    for reader in readers.iter_mut() {
+        stream_into(&mut hasher, reader)?;
+    }
+    Ok(hasher.finish())
+}
+
+/**
+Write `data` followed by an 8-byte big-endian [hash_bytes] trailer.
+
+The framed data can be read back and verified with [read_verify_checksum].
+*/
+pub fn write_with_checksum<W: Write>(writer: &mut W, data: &[u8]) -> io::Result<()> {
+    writer.write_all(data)?;
+    writer.write_all(&hash_bytes(data).to_be_bytes())
+}
+
+/**
+Read all data written by [write_with_checksum], split off the 8-byte
+big-endian trailer and verify it against the [hash_bytes] of the data.
+
+Returns the data without the trailer, or an [io::ErrorKind::InvalidData]
+error if the input is too short or the checksum doesn't match.
+*/
+pub fn read_verify_checksum<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut data: Vec<u8> = Vec::new();
+    reader.read_to_end(&mut data)?;
+    if data.len() < CHECKSUM_TRAILER_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "input too short for a checksum trailer",
+        ));
+    }
+
+    let trailer: Vec<u8> = data.split_off(data.len() - CHECKSUM_TRAILER_SIZE);
+    let expected = u64::from_be_bytes(trailer.try_into().unwrap());
+    if hash_bytes(&data) != expected {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "checksum mismatch",
+        ));
+    }
+    Ok(data)
+}
+
+/**
+Check whether two readers likely hold identical content, without loading
+either of them into memory.
+
+Both readers are streamed and their 128-bit digests compared. Reading `b`
+stops early as soon as it turns out to be longer than `a`.
+
+NOTE: this is a *probabilistic* equality: a hash collision could make two
+different inputs compare equal (with a negligible 2^-128 chance). It's
+meant as a fast pre-filter, e.g. for deduplicating large files.
+*/
+pub fn readers_equal<R1: Read, R2: Read>(a: &mut R1, b: &mut R2) -> io::Result<bool> {
+    let mut hasher_a: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    let len_a: u64 = stream_into(&mut hasher_a, a)?;
+
+    let mut hasher_b: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    let len_b: u64 = stream_into(&mut hasher_b, &mut b.take(len_a.saturating_add(1)))?;
+
+    if len_a != len_b {
+        return Ok(false);
+    }
+    Ok(hasher_a.finish_128() == hasher_b.finish_128())
+}
+
+/* --------------------------------- */
+
+/**
+A tee-style [Write]r which forwards all bytes to an inner writer while
+hashing them, so a checksum can be computed while streaming e.g. to a
+file or a socket.
+
+Only the bytes actually accepted by the inner writer are hashed.
+*/
+#[derive(Debug)]
+pub struct HashingWriter<W: Write> {
+    inner: W,
+    hasher: CustomXxh3Hasher,
+}
+
+impl<W: Write> HashingWriter<W> {
+    /// Wrap a writer, hashing with a default [CustomXxh3Hasher].
+    pub fn new(inner: W) -> Self {
+        Self::with_hasher(inner, CustomXxh3Hasher::default())
+    }
+
+    /// Wrap a writer, hashing with the given [CustomXxh3Hasher].
+    pub fn with_hasher(inner: W, hasher: CustomXxh3Hasher) -> Self {
+        Self { inner, hasher }
+    }
+
+    /// Get the digest of all bytes written through so far.
+    #[inline]
+    pub fn digest(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    /// Get a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Unwrap into the inner writer and the hasher.
+    pub fn into_parts(self) -> (W, CustomXxh3Hasher) {
+        (self.inner, self.hasher)
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n: usize = self.inner.write(buf)?;
+        self.hasher.write(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/**
+A tee-style [Read]er which hashes all bytes as they are read from an inner
+reader, so a stream can be hashed while a downstream consumer (e.g. a
+parser) reads it normally.
+
+Only the bytes actually read are hashed.
+*/
+#[derive(Debug)]
+pub struct HashingReader<R: Read> {
+    inner: R,
+    hasher: CustomXxh3Hasher,
+}
+
+impl<R: Read> HashingReader<R> {
+    /// Wrap a reader, hashing with a default [CustomXxh3Hasher].
+    pub fn new(inner: R) -> Self {
+        Self::with_hasher(inner, CustomXxh3Hasher::default())
+    }
+
+    /// Wrap a reader, hashing with the given [CustomXxh3Hasher].
+    pub fn with_hasher(inner: R, hasher: CustomXxh3Hasher) -> Self {
+        Self { inner, hasher }
+    }
+
+    /// Get the digest of all bytes read so far.
+    #[inline]
+    pub fn digest(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    /// Get a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Unwrap into the inner reader and the hasher.
+    pub fn into_parts(self) -> (R, CustomXxh3Hasher) {
+        (self.inner, self.hasher)
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n: usize = self.inner.read(buf)?;
+        self.hasher.write(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/* --------------------------------- */
+
+/**
+A [Write]r which only hashes the bytes written into it and discards them,
+for computing what a writer would produce without keeping the output.
+Like a [HashingWriter] around [io::sink], minus the unused wrapper.
+*/
+#[derive(Clone, Debug, Default)]
+pub struct HashSink {
+    hasher: CustomXxh3Hasher,
+}
+
+impl HashSink {
+    /// Create a new sink hashing with a default [CustomXxh3Hasher].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new sink hashing with the given [CustomXxh3Hasher].
+    pub fn with_hasher(hasher: CustomXxh3Hasher) -> Self {
+        Self { hasher }
+    }
+
+    /// Get the digest of all bytes written so far.
+    #[inline]
+    pub fn digest(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    /// Unwrap into the hasher.
+    pub fn into_hasher(self) -> CustomXxh3Hasher {
+        self.hasher
+    }
+}
+
+impl Write for HashSink {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.hasher.write(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/* ######################################################################### */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_DATA: &[u8] = b"Hello, world!";
+
+    #[test]
+    fn test_checksum_round_trip() {
+        let mut framed: Vec<u8> = Vec::new();
+        write_with_checksum(&mut framed, TEST_DATA).unwrap();
+        assert_eq!(framed.len(), TEST_DATA.len() + CHECKSUM_TRAILER_SIZE);
+
+        let data = read_verify_checksum(&mut framed.as_slice()).unwrap();
+        assert_eq!(data, TEST_DATA);
+    }
+
+    #[test]
+    fn test_checksum_corruption() {
+        let mut framed: Vec<u8> = Vec::new();
+        write_with_checksum(&mut framed, TEST_DATA).unwrap();
+        framed[0] ^= 0x01;
+
+        let err = read_verify_checksum(&mut framed.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let err = read_verify_checksum(&mut &b"short"[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_ingest_limits() {
+        let mut hasher = CustomXxh3Hasher::default();
+        assert_eq!(hasher.ingest(&mut &TEST_DATA[..], 100).unwrap(), 13);
+        assert_eq!(hasher.finish(), hash_bytes(TEST_DATA));
+
+        let mut hasher = CustomXxh3Hasher::default();
+        assert_eq!(hasher.ingest(&mut &TEST_DATA[..], 13).unwrap(), 13);
+        assert_eq!(hasher.finish(), hash_bytes(TEST_DATA));
+
+        let mut hasher = CustomXxh3Hasher::default();
+        let err = hasher.ingest(&mut &TEST_DATA[..], 12).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(hasher.bytes_written(), 12);
+
+        let large = vec![0xA5u8; 3 * READ_BUF_SIZE + 7];
+        let mut hasher = CustomXxh3Hasher::default();
+        assert!(hasher
+            .ingest(&mut large.as_slice(), 2 * READ_BUF_SIZE as u64)
+            .is_err());
+        let mut hasher = CustomXxh3Hasher::default();
+        assert_eq!(
+            hasher.ingest(&mut large.as_slice(), u64::MAX).unwrap(),
+            large.len() as u64
+        );
+        assert_eq!(hasher.finish(), hash_bytes(&large));
+    }
+
+    #[test]
+    fn test_readers_equal() {
+        let data = vec![0x5Au8; 2 * READ_BUF_SIZE + 3];
+        let mut other = data.clone();
+        other[READ_BUF_SIZE] ^= 0x01;
+
+        assert!(readers_equal(&mut data.as_slice(), &mut data.clone().as_slice()).unwrap());
+        assert!(!readers_equal(&mut data.as_slice(), &mut other.as_slice()).unwrap());
+        assert!(!readers_equal(&mut data.as_slice(), &mut &data[1..]).unwrap());
+        assert!(!readers_equal(&mut &data[1..], &mut data.as_slice()).unwrap());
+        assert!(readers_equal(&mut &b""[..], &mut &b""[..]).unwrap());
+    }
+
+    #[test]
+    fn test_hashing_writer() {
+        let mut writer = HashingWriter::new(Vec::new());
+        writer.write_all(TEST_DATA).unwrap();
+        writer.write_all(&[0xA5; 1000]).unwrap();
+        writer.flush().unwrap();
+
+        let digest = writer.digest();
+        let (written, hasher) = writer.into_parts();
+        assert_eq!(written.len(), TEST_DATA.len() + 1000);
+        assert_eq!(digest, hash_bytes(&written));
+        assert_eq!(hasher.bytes_written(), written.len() as u64);
+    }
+
+    #[test]
+    fn test_hashing_reader() {
+        let data = [TEST_DATA, &[0xA5; 1000]].concat();
+        let mut reader = HashingReader::new(data.as_slice());
+
+        let mut head = [0u8; 5];
+        reader.read_exact(&mut head).unwrap();
+        assert_eq!(reader.digest(), hash_bytes(b"Hello"));
+
+        let mut consumed = head.to_vec();
+        reader.read_to_end(&mut consumed).unwrap();
+        assert_eq!(consumed, data);
+        assert_eq!(reader.digest(), hash_bytes(&consumed));
+    }
+
+    #[test]
+    fn test_hash_reader_bounded() {
+        let data = vec![7u8; 20_000];
+
+        assert_eq!(
+            hash_reader_bounded(&mut data.as_slice(), 20_000).unwrap(),
+            Some(hash_bytes(&data))
+        );
+        assert_eq!(
+            hash_reader_bounded(&mut data.as_slice(), 19_999).unwrap(),
+            None
+        );
+        assert_eq!(hash_reader_bounded(&mut io::repeat(0), 100).unwrap(), None);
+        assert_eq!(
+            hash_reader_bounded(&mut io::empty(), 0).unwrap(),
+            Some(crate::hash_empty())
+        );
+    }
+
+    #[test]
+    fn test_hash_sink() {
+        let mut sink = HashSink::new();
+        let world = "world";
+        write!(sink, "Hello, {world}!").unwrap();
+        sink.write_all(&[0xA5; 1000]).unwrap();
+
+        let mut expected = b"Hello, world!".to_vec();
+        expected.extend_from_slice(&[0xA5; 1000]);
+        assert_eq!(sink.digest(), hash_bytes(&expected));
+        assert_eq!(sink.into_hasher().bytes_written(), expected.len() as u64);
+    }
+
+    #[test]
+    fn test_hash_readers() {
+        let part1 = vec![1u8; 10_000];
+        let part2 = b"Hello, world!".to_vec();
+        let mut readers: Vec<&[u8]> = vec![&part1, &[], &part2, &[]];
+        let concat = [part1.as_slice(), part2.as_slice()].concat();
+
+        assert_eq!(hash_readers(&mut readers).unwrap(), hash_bytes(&concat));
+        assert_eq!(hash_readers::<&[u8]>(&mut []).unwrap(), crate::hash_empty());
+    }
+}
@@ -4,26 +4,100 @@
 use std::{
     fmt::{self, Debug, Formatter},
     hash::{BuildHasher, Hash, Hasher, RandomState},
-    ops::{Deref, DerefMut},
+    num::NonZeroU64,
+    ops::{Deref, DerefMut, Range},
+    sync::atomic::{AtomicU64, Ordering},
 };
 use xxhash_rust::{
-    const_xxh3::const_custom_default_secret,
-    xxh3::{xxh3_64, xxh3_64_with_secret, Xxh3, Xxh3Builder},
+    const_xxh3::{const_custom_default_secret, xxh3_64_with_secret as const_xxh3_64_with_secret},
+    xxh3::{xxh3_128_with_secret, xxh3_64, xxh3_64_with_secret, Xxh3, Xxh3Builder},
 };
 
+#[cfg(feature = "cbor")]
+mod cbor;
+mod codec;
+mod collections;
+#[cfg(feature = "crc32")]
+mod dual;
+mod io;
+mod migration;
+mod portable;
+mod ring;
+mod sketch;
+
+#[cfg(feature = "cbor")]
+pub use cbor::hash_cbor;
+pub use codec::{
+    content_id, digest128_from_base64url, digest128_to_base64url, digest_from_base64url,
+    digest_to_base64url, fingerprint_glyphs,
+};
+pub use collections::{
+    hash_btreemap_ordered, hash_collection, hash_config, hash_grid, hash_map_unordered,
+    hash_tuple2, hash_tuple3, hash_tuple4, hash_tuple5, hash_tuple6, Xxh3DedupSet,
+};
+#[cfg(feature = "crc32")]
+pub use dual::DualChecksum;
+pub use io::{
+    hash_reader_bounded, hash_readers, read_verify_checksum, readers_equal, write_with_checksum,
+    HashSink, HashingReader, HashingWriter,
+};
+pub use migration::{MigrationBuilder, MigrationHasher};
+pub use portable::hash_path_portable;
+pub use ring::{ConsistentHashRing, DEFAULT_VIRTUAL_NODES};
+pub use sketch::{window_hashes, HashReservoir, MinHashSketch, SimHash};
+
 #[cfg(feature = "size_of")]
 use {
     size_of::{Context, SizeOf},
     std::mem::size_of,
 };
 
-const XXH3_SECRET_SIZE: usize = 192;
+/// Size of an Xxh3 secret in bytes, as required by e.g. [CustomXxh3Hasher::with_secret].
+pub const XXH3_SECRET_SIZE: usize = 192;
 const XXH3_SECRET_SEED: u64 = 0xDEAD_BEEF_FEED_F00D;
 const XXH3_SECRET: [u8; XXH3_SECRET_SIZE] = const_custom_default_secret(XXH3_SECRET_SEED);
 
+// Several constructors copy secrets into fixed-size arrays and rely on these.
+const _: () = assert!(XXH3_SECRET.len() == XXH3_SECRET_SIZE);
+const _: () = assert!(
+    XXH3_SECRET_SIZE >= 136,
+    "Xxh3 requires secrets of at least 136 bytes"
+);
+
+/// Source of per-thread ids for [CustomXxh3Hasher::thread_local_seeded].
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// The per-thread seed: a SplitMix64 scramble of a process-wide counter
+    /// value taken on the first use in each thread.
+    static THREAD_SEED: u64 = {
+        let mut id: u64 = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+        splitmix64(&mut id)
+    };
+}
+
 #[derive(Debug)]
 pub enum Xxh3Error {
     InvalidSecretSize(usize),
+    InvalidRange {
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+    /// An operation on an existing hasher was given a secret whose size
+    /// doesn't match the secret the hasher was built with.
+    SecretMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    /// An encoded digest string was malformed (wrong length, characters
+    /// outside the alphabet or non-canonical trailing bits).
+    InvalidEncoding,
+    /// A digest was requested from a hasher which hasn't been fed any bytes.
+    EmptyInput,
+    /// A structured value contained a variant with no known canonical
+    /// encoding, so it can't be hashed deterministically.
+    UnsupportedValue,
 }
 
 /// Build a new [Xxh3] hasher with a given seed and Xxh3 default secret.
@@ -73,24 +147,233 @@ pub struct CustomXxh3Hasher {
     xxh: Xxh3,
     seed: u64,
     custom_secret: Option<[u8; XXH3_SECRET_SIZE]>,
+    prime: Vec<u8>,
+    bytes_written: u64,
+    endian: Endian,
+    checkpoint: Option<Box<Xxh3>>,
+}
+
+/// Byte order policy for the primitive integer `write_*` methods of
+/// [CustomXxh3Hasher] (see [CustomXxh3Hasher::with_endianness]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Endian {
+    /// The target's native byte order (standard [Hasher] behavior).
+    #[default]
+    Native,
+    Little,
+    Big,
+}
+
+/// Post-processing applied to a raw 64-bit digest, for matching an external
+/// system's digest convention (see [CustomXxh3Hasher::finish_transformed]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputTransform {
+    /// Return the digest unchanged.
+    #[default]
+    Identity,
+    /// Reverse the byte order of the digest (`u64::swap_bytes`).
+    ByteSwap,
+    /// Rotate the digest left by the given number of bits (modulo 64).
+    RotateLeft(u32),
+    /// Keep only the digest bits set in the mask (bitwise AND), e.g.
+    /// `Mask(0xFFFF_FFFF)` for a 32-bit digest.
+    Mask(u64),
+}
+
+impl OutputTransform {
+    /// Apply this transform to a digest.
+    #[inline]
+    pub const fn apply(self, digest: u64) -> u64 {
+        match self {
+            Self::Identity => digest,
+            Self::ByteSwap => digest.swap_bytes(),
+            Self::RotateLeft(n) => digest.rotate_left(n),
+            Self::Mask(mask) => digest & mask,
+        }
+    }
+}
+
+/**
+A plain configuration for building a [CustomXxh3Hasher]: a seed and an
+optional custom secret (Xxh3 defaults are used if it's not set).
+
+With the `arbitrary` feature enabled, this implements `arbitrary::Arbitrary`
+so fuzzers can generate valid hasher configurations.
+*/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Xxh3Config {
+    pub seed: u64,
+    pub secret: Option<[u8; XXH3_SECRET_SIZE]>,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Xxh3Config {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            seed: u.arbitrary()?,
+            secret: u.arbitrary()?,
+        })
+    }
+}
+
+/// A summary of a hashed stream: its digest and the number of bytes hashed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashSummary {
+    pub digest: u64,
+    pub bytes: u64,
 }
 
 impl CustomXxh3Hasher {
-    /// Create a new [CustomXxh3Hasher] with a given seed.
-    pub fn new(seed: u64) -> Self {
+    /// Assemble a [CustomXxh3Hasher] around an already built [Xxh3].
+    #[inline]
+    fn from_xxh3(xxh: Xxh3, seed: u64, custom_secret: Option<[u8; XXH3_SECRET_SIZE]>) -> Self {
         Self {
-            xxh: build_xxh3_with_seed(seed),
+            xxh,
             seed,
-            custom_secret: None,
+            custom_secret,
+            prime: Vec::new(),
+            bytes_written: 0,
+            endian: Endian::Native,
+            checkpoint: None,
         }
     }
 
+    /// Create a new [CustomXxh3Hasher] with a given seed.
+    pub fn new(seed: u64) -> Self {
+        Self::from_xxh3(build_xxh3_with_seed(seed), seed, None)
+    }
+
+    /**
+    Create a new [CustomXxh3Hasher] with a given seed and a secret derived
+    from that same seed, so that the seed affects inputs of every length.
+
+    A plain seeded Xxh3 only uses the seed for inputs of up to 240 bytes,
+    while longer ones are hashed with the (shared) secret alone. Deriving
+    the secret from the seed closes that gap for per-key hashers.
+    */
+    pub(crate) fn with_derived_secret(seed: u64) -> Self {
+        let secret: [u8; XXH3_SECRET_SIZE] = const_custom_default_secret(seed);
+        Self::from_xxh3(
+            build_xxh3_with_secret_and_seed(secret, seed),
+            seed,
+            Some(secret),
+        )
+    }
+
+    /**
+    Create a new [CustomXxh3Hasher] with a seed which is stable within the
+    current thread but differs across threads, e.g. for sharded counters.
+
+    The thread id is derived from a process-wide counter, incremented the
+    first time each thread calls this, and scrambled with SplitMix64 into
+    the seed. Seeds therefore depend on the order in which threads first
+    hash, and are not stable across runs of the program. The secret is
+    derived from the seed too, so digests differ across threads for
+    inputs of any length.
+    */
+    pub fn thread_local_seeded() -> Self {
+        Self::with_derived_secret(THREAD_SEED.with(|seed| *seed))
+    }
+
     /// Create a new [CustomXxh3Hasher] with Xxh3 defaults.
     pub fn new_xxh3_defaults() -> Self {
+        Self::from_xxh3(Xxh3Builder::new().build(), 0, None)
+    }
+
+    /**
+    Create a new [CustomXxh3Hasher] with a given seed, scoped to a domain.
+
+    The domain tag is mixed in (length-framed) before any user data, so two
+    hashers with different domains produce different digests for identical
+    subsequent input. The domain is kept as the hasher's prime (see
+    [set_prime](Self::set_prime)), so it survives a [reset](Self::reset).
+    */
+    pub fn with_domain(domain: &[u8], seed: u64) -> Self {
+        let mut prime = Vec::with_capacity(domain.len() + 8);
+        prime.extend_from_slice(&(domain.len() as u64).to_le_bytes());
+        prime.extend_from_slice(domain);
+
+        let mut hasher = Self::new(seed);
+        hasher.prime = prime;
+        hasher.apply_prime();
+        hasher
+    }
+
+    /**
+    Create a new [CustomXxh3Hasher] with a given seed, salted with a schema
+    version. Bumping the version changes every digest, which makes it an
+    easy way to invalidate persisted or cached digests.
+
+    The version is written as a framed little-endian `u32` prime, i.e. this
+    equals [with_domain](Self::with_domain) with `version.to_le_bytes()`.
+    */
+    pub fn versioned(version: u32, seed: u64) -> Self {
+        Self::with_domain(&version.to_le_bytes(), seed)
+    }
+
+    /// Create a new [CustomXxh3Hasher] from a [Xxh3Config].
+    pub fn from_config(config: Xxh3Config) -> Self {
+        match config.secret {
+            Some(secret) => Self::from_xxh3(
+                build_xxh3_with_secret_and_seed(secret, config.seed),
+                config.seed,
+                Some(secret),
+            ),
+            None => Self::new(config.seed),
+        }
+    }
+
+    /// Create a new [CustomXxh3Hasher] seeded from a human-readable key.
+    /// The seed is [hash_bytes] of the key, so it's deterministic per key,
+    /// and the secret is derived from that seed, so the key matters for
+    /// inputs of any length.
+    pub fn from_key(key: &str) -> Self {
+        Self::with_derived_secret(hash_bytes(key.as_bytes()))
+    }
+
+    /**
+    Create a new [CustomXxh3Hasher] with a given seed and a byte order
+    policy for the primitive integer `write_*` methods (`write_u32()`,
+    `write_i64()`, `write_usize()` etc.), making their output identical
+    across platforms with [Endian::Little] or [Endian::Big].
+
+    This also covers integers hashed through [Hash] impls, as those call
+    the `write_*` methods. However, `std` hashes *slices* of integers as
+    raw native-endian memory via [write](Hasher::write), bypassing the
+    policy. Byte slices and strings are unaffected by byte order anyway.
+    */
+    pub fn with_endianness(seed: u64, endian: Endian) -> Self {
+        let mut hasher: Self = Self::new(seed);
+        hasher.endian = endian;
+        hasher
+    }
+
+    /**
+    Preset for maximum speed: Xxh3 defaults (seed 0, default secret) and
+    native-endian integer writes, i.e. [new_xxh3_defaults](Self::new_xxh3_defaults).
+
+    Digests of integers written via the `write_*` methods may differ
+    between little- and big-endian targets. Digests also differ from
+    those of the [portable](Self::portable) preset.
+    */
+    pub fn fast() -> Self {
+        Self::new_xxh3_defaults()
+    }
+
+    /**
+    Preset for cross-platform digest stability: our custom secret
+    [XXH3_SECRET] (as in [Default]) and little-endian integer writes
+    (see [with_endianness](Self::with_endianness)), so the same sequence
+    of writes gives the same digest on every target.
+
+    The byte order conversion is a no-op on little-endian targets and
+    costs a byte swap per integer elsewhere. Digests differ from those of
+    the [fast](Self::fast) preset.
+    */
+    pub fn portable() -> Self {
         Self {
-            xxh: Xxh3Builder::new().build(),
-            seed: 0,
-            custom_secret: None,
+            endian: Endian::Little,
+            ..Self::default()
         }
     }
 
@@ -101,11 +384,14 @@ impl CustomXxh3Hasher {
         }
         let mut arr = [0u8; XXH3_SECRET_SIZE];
         arr.copy_from_slice(secret);
-        Ok(Self {
-            xxh: build_xxh3_with_secret(arr),
-            seed: 0,
-            custom_secret: Some(arr),
-        })
+        Ok(Self::from_secret_array(arr))
+    }
+
+    /// Build a Xxh3 hasher with a custom secret given as an owned array.
+    /// Unlike [with_secret](Self::with_secret), this needs no validation,
+    /// as the type guarantees the secret size.
+    pub fn from_secret_array(secret: [u8; XXH3_SECRET_SIZE]) -> Self {
+        Self::from_xxh3(build_xxh3_with_secret(secret), 0, Some(secret))
     }
 
     /// Build a Xxh3 hasher with a custom secret and seed
@@ -115,11 +401,11 @@ impl CustomXxh3Hasher {
         }
         let mut arr = [0u8; XXH3_SECRET_SIZE];
         arr.copy_from_slice(secret);
-        Ok(Self {
-            xxh: build_xxh3_with_secret_and_seed(arr, seed),
+        Ok(Self::from_xxh3(
+            build_xxh3_with_secret_and_seed(arr, seed),
             seed,
-            custom_secret: Some(arr),
-        })
+            Some(arr),
+        ))
     }
 
     /// Get the seed value used by this hasher.
@@ -127,27 +413,450 @@ impl CustomXxh3Hasher {
         self.seed
     }
 
+    /**
+    Get the number of bytes written into this hasher since it was created
+    or last [reset](Self::reset), including any prime prefix.
+
+    NOTE: bytes fed directly into the underlying [Xxh3] (via [DerefMut])
+    bypass the counter.
+    */
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Get the 128-bit digest of the data written so far. Like
+    /// [finish](Hasher::finish), this doesn't reset the hasher.
+    #[inline]
+    pub fn finish_128(&self) -> u128 {
+        self.xxh.digest128()
+    }
+
+    /// Get the current digest as a [NonZeroU64], for contexts where zero is
+    /// a sentinel. A zero digest is remapped to `u64::MAX` (so `u64::MAX`
+    /// then has two preimages); all other digests pass through unchanged.
+    #[inline]
+    pub fn finish_nonzero(&self) -> NonZeroU64 {
+        nonzero_digest(self.finish())
+    }
+
+    /**
+    Get the current digest, or [Xxh3Error::EmptyInput] if nothing has been
+    written (per the [byte counter](Self::bytes_written)). This is opt-in
+    strictness for pipelines where empty input signals a logic error; the
+    infallible [finish](Hasher::finish) is unaffected.
+
+    NOTE: a [prime](Self::set_prime) prefix counts as written bytes.
+    */
+    pub fn try_finish(&self) -> Result<u64, Xxh3Error> {
+        match self.bytes_written {
+            0 => Err(Xxh3Error::EmptyInput),
+            _ => Ok(self.finish()),
+        }
+    }
+
+    /// Get a domain-tagged digest of the current state: the `tag` is mixed in
+    /// with [write_u64](Hasher::write_u64) on an internal [fork](Self::fork),
+    /// so the hasher itself is left untouched and several tagged digests can
+    /// be derived from one accumulated state.
+    pub fn finish_finalized(&self, tag: u64) -> u64 {
+        let mut fork: Self = self.fork();
+        fork.write_u64(tag);
+        fork.finish()
+    }
+
+    /// Get the current digest mapped to a fraction in `[0, 1)`, e.g. for
+    /// sampling: `if hasher.finish_fraction() < 0.01 { keep }`. See
+    /// [hash_bytes_fraction] for the mapping.
+    #[inline]
+    pub fn finish_fraction(&self) -> f64 {
+        digest_fraction(self.finish())
+    }
+
+    /// Get the current digest split into two 32-bit halves `(high, low)`:
+    /// `high` holds bits 63..32 of the digest and `low` bits 31..0, so that
+    /// `(high as u64) << 32 | low as u64` is the full digest.
+    #[inline]
+    pub fn finish_halves(&self) -> (u32, u32) {
+        let digest: u64 = self.finish();
+        ((digest >> 32) as u32, digest as u32)
+    }
+
+    /**
+    Get the current digest folded to a native `usize`, e.g. for portable
+    bucket indices. On 64-bit targets this is the digest itself. On 32-bit
+    (and 16-bit) targets the digest is first folded by XORing its high and
+    low 32-bit halves (see [finish_halves](Self::finish_halves)), and the
+    low `size_of::<usize>()` bytes of the result are returned, so all 64
+    digest bits contribute.
+    */
+    #[inline]
+    pub fn finish_usize(&self) -> usize {
+        #[cfg(target_pointer_width = "64")]
+        return self.finish() as usize;
+        #[cfg(not(target_pointer_width = "64"))]
+        {
+            let (high, low) = self.finish_halves();
+            (high ^ low) as usize
+        }
+    }
+
+    /// Get a nonce-derived digest of the current state: the nonce is written
+    /// framed with its length (see [write_framed](Self::write_framed)) on an
+    /// internal [fork](Self::fork), so the hasher itself is not mutated and
+    /// many distinct keys can be derived from one accumulated base.
+    pub fn finish_with_nonce(&self, nonce: &[u8]) -> u64 {
+        let mut fork: Self = self.fork();
+        fork.write_framed(nonce);
+        fork.finish()
+    }
+
+    /// Get a length-strengthened digest (Merkle-Damgård style): the total
+    /// [byte count](Self::bytes_written) is appended as a framed `u64` (see
+    /// [write_framed](Self::write_framed)) on an internal [fork](Self::fork),
+    /// leaving the hasher untouched. This is a distinct output from
+    /// [finish](Hasher::finish), and not comparable with it.
+    pub fn finish_with_length(&self) -> u64 {
+        let mut fork: Self = self.fork();
+        fork.write_framed(&self.bytes_written.to_le_bytes());
+        fork.finish()
+    }
+
+    /// Get the current digest post-processed with the given [OutputTransform].
+    #[inline]
+    pub fn finish_transformed(&self, t: OutputTransform) -> u64 {
+        t.apply(self.finish())
+    }
+
+    /// Get a `(length, digest)` dedup key for the data written so far, using
+    /// the [byte counter](Self::bytes_written). See the free [dedup_key].
+    #[inline]
+    pub fn dedup_key(&self) -> (u64, u64) {
+        (self.bytes_written, self.finish())
+    }
+
+    /// Get the current digest together with the number of bytes hashed.
+    pub fn finish_summary(&self) -> HashSummary {
+        HashSummary {
+            digest: self.finish(),
+            bytes: self.bytes_written,
+        }
+    }
+
+    /**
+    Format the current digest as a line in the `xxhsum -H3` output style:
+    `XXH3_` + the digest as 16 lower-case hex digits (big-endian canonical
+    form), two spaces and the file name, e.g. `XXH3_2d06800538d394c2  file`.
+
+    NOTE: `xxhsum` uses Xxh3 defaults (seed 0, default secret), so only the
+    output of e.g. [new_xxh3_defaults](Self::new_xxh3_defaults) hashers can be
+    verified with `xxhsum -c`.
+    */
+    pub fn finish_xxhsum_line(&self, filename: &str) -> String {
+        format!("XXH3_{:016x}  {}", self.finish(), filename)
+    }
+
+    /**
+    Record a checkpoint and return the current digest. From now on, the
+    digest of only the content written after the checkpoint is available
+    via [since_checkpoint](Self::since_checkpoint).
+
+    NOTE: while a checkpoint is held, every write is hashed twice, and a
+    second heap-allocated [Xxh3] state (several hundred bytes) is kept.
+    A [reset](Self::reset) drops the checkpoint.
+    */
+    pub fn checkpoint(&mut self) -> u64 {
+        let mut fresh: Xxh3 = self.xxh.clone();
+        fresh.reset();
+        match &mut self.checkpoint {
+            Some(state) => **state = fresh,
+            None => self.checkpoint = Some(Box::new(fresh)),
+        }
+        self.finish()
+    }
+
+    /// Get the digest of the content written after the last
+    /// [checkpoint](Self::checkpoint), or of everything written so far
+    /// if no checkpoint has been recorded.
+    pub fn since_checkpoint(&self) -> u64 {
+        match &self.checkpoint {
+            Some(state) => state.digest(),
+            None => self.finish(),
+        }
+    }
+
     /// Get the secret value used by this hasher, if it's not the default.
     fn secret(&self) -> Option<&[u8; XXH3_SECRET_SIZE]> {
         self.custom_secret.as_ref()
     }
 
+    /// Check whether this is an unseeded hasher running on [XXH3_SECRET]
+    /// without recording it as a custom secret, as [default](Self::default)
+    /// builds it. Probed with the empty input digest, which is secret bound.
+    fn runs_on_default_secret(&self) -> bool {
+        if self.custom_secret.is_some() || self.seed != 0 {
+            return false;
+        }
+        let mut probe: Xxh3 = self.xxh.clone();
+        probe.reset();
+        probe.digest() == xxh3_64_with_secret(&[], &XXH3_SECRET)
+    }
+
     /// Return the current hash digest and reset the hasher to its initial state.
+    /// The prime (if any) is re-applied after the reset.
     #[inline]
     pub fn reset(&mut self) -> u64 {
         let state: u64 = self.finish();
         self.xxh.reset();
+        self.bytes_written = 0;
+        self.checkpoint = None;
+        self.apply_prime();
         state
     }
 
+    /**
+    Set a "prime" prefix which is written into the hasher after every
+    [reset](Self::reset), making pooled, domain-scoped hashers reusable
+    without re-priming them manually. The current state is left untouched.
+
+    The prime is kept by [change_seed](Self::change_seed) and re-applied
+    to the rebuilt hasher. An empty slice clears the prime.
+    */
+    pub fn set_prime(&mut self, bytes: &[u8]) {
+        self.prime.clear();
+        self.prime.extend_from_slice(bytes);
+    }
+
+    /// Get the prime prefix of this hasher (empty if not set).
+    pub fn prime(&self) -> &[u8] {
+        &self.prime
+    }
+
+    /// Write the prime prefix (if any) into the hasher.
+    #[inline]
+    fn apply_prime(&mut self) {
+        if !self.prime.is_empty() {
+            self.xxh.update(&self.prime);
+            self.bytes_written += self.prime.len() as u64;
+        }
+    }
+
     /// Change the seed value used by this hasher.
     ///
-    /// NOTE: all current state **will** be lost.
+    /// NOTE: all current state **will** be lost, but the prime and the
+    /// endianness policy are kept.
     pub fn change_seed(&mut self, seed: u64) {
-        if self.secret().is_some() {
-            *self = Self::with_secret_and_seed(self.secret().unwrap(), seed).unwrap();
+        let fresh: Self = match self.secret() {
+            Some(secret) => Self::with_secret_and_seed(secret, seed).unwrap(),
+            None => Self::new(seed),
+        };
+        self.rebuild_from(fresh);
+    }
+
+    /// Replace this hasher with a freshly built one, carrying over the
+    /// prime and endianness policy, and re-applying the prime.
+    fn rebuild_from(&mut self, fresh: Self) {
+        let prime: Vec<u8> = std::mem::take(&mut self.prime);
+        let endian: Endian = self.endian;
+        *self = fresh;
+        self.prime = prime;
+        self.endian = endian;
+        self.apply_prime();
+    }
+
+    /**
+    Replace the secret used by this hasher, preserving the current seed.
+    The new secret must be the same size as the current one, otherwise
+    [Xxh3Error::SecretMismatch] is returned and the hasher is left as is.
+
+    NOTE: all current state **will** be lost, but the prime and the
+    endianness policy are kept.
+    */
+    pub fn change_secret(&mut self, secret: &[u8]) -> Result<(), Xxh3Error> {
+        if secret.len() != XXH3_SECRET_SIZE {
+            return Err(Xxh3Error::SecretMismatch {
+                expected: XXH3_SECRET_SIZE,
+                actual: secret.len(),
+            });
+        }
+        let fresh: Self = Self::with_secret_and_seed(secret, self.seed)?;
+        self.rebuild_from(fresh);
+        Ok(())
+    }
+
+    /**
+    Compute what `bytes` would hash to with this hasher's secret and prime
+    under a different `seed`, without touching the current state. The
+    secret is kept even for a [default](Self::default) hasher, unlike with
+    [change_seed](Self::change_seed), which rebuilds those with Xxh3's
+    default secret.
+
+    NOTE: with a non-zero `seed`, Xxh3 only consults the secret when the
+    total input (prime included) is longer than 240 bytes. Shorter inputs
+    hash as with a plain [new](Self::new) hasher, whatever the secret.
+
+    NOTE: already streamed content can't be replayed under another seed,
+    since [Xxh3] doesn't keep its input around. This is therefore scoped
+    to the oneshot case: pass the complete input here instead of
+    [write](Hasher::write)-ing it first. That's also why this is named
+    `hash_with_alt_seed` and takes the bytes, instead of finishing the
+    current content like a `finish_with_alt_seed` would.
+    */
+    pub fn hash_with_alt_seed(&self, bytes: &[u8], seed: u64) -> u64 {
+        let mut hasher: Self = self.fork();
+        if self.runs_on_default_secret() {
+            let fresh: Self = Self::from_xxh3(
+                build_xxh3_with_secret_and_seed(XXH3_SECRET, seed),
+                seed,
+                None,
+            );
+            hasher.rebuild_from(fresh);
         } else {
-            *self = Self::new(seed);
+            hasher.change_seed(seed);
+        }
+        hasher.write(bytes);
+        hasher.finish()
+    }
+
+    /// Drop any custom secret and rebuild the hasher with Xxh3 defaults,
+    /// preserving the current seed.
+    ///
+    /// NOTE: all current state **will** be lost, but the prime and the
+    /// endianness policy are kept.
+    pub fn without_secret(mut self) -> Self {
+        self.rebuild_from(Self::new(self.seed));
+        self
+    }
+
+    /// Rebuild the hasher with our default secret [XXH3_SECRET],
+    /// preserving the current seed. With a non-zero seed, Xxh3 only
+    /// consults the secret for inputs longer than 240 bytes.
+    ///
+    /// NOTE: all current state **will** be lost, but the prime and the
+    /// endianness policy are kept.
+    pub fn with_default_secret(mut self) -> Self {
+        let fresh: Self = Self::from_xxh3(
+            build_xxh3_with_secret_and_seed(XXH3_SECRET, self.seed),
+            self.seed,
+            Some(XXH3_SECRET),
+        );
+        self.rebuild_from(fresh);
+        self
+    }
+
+    /// Fork the hasher: the returned copy continues from the current state
+    /// independently of this one.
+    #[inline]
+    pub fn fork(&self) -> Self {
+        self.clone()
+    }
+
+    /// Write a byte slice prefixed with its length as a little-endian `u64`,
+    /// so that adjacent writes can't bleed into each other.
+    #[inline]
+    pub fn write_framed(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    /// Write a [char] as its `u32` code point in little-endian byte order,
+    /// regardless of the [Endian] policy or platform, e.g. `'A'` is written
+    /// as `[0x41, 0, 0, 0]`. See [hash_str_chars] for whole strings.
+    #[inline]
+    pub fn write_char(&mut self, c: char) {
+        self.write(&(c as u32).to_le_bytes());
+    }
+
+    /**
+    Write a run of `count` identical bytes as the byte followed by the count
+    (little-endian `u64`), without materializing the run. This makes hashing
+    sparse buffers cheap regardless of the run lengths.
+
+    NOTE: this is a separate encoding scheme, and produces a DIFFERENT
+    digest than writing the expanded bytes. Digests are only comparable with
+    others computed with the same run-length encoding of the data.
+    */
+    #[inline]
+    pub fn write_run(&mut self, byte: u8, count: u64) {
+        self.write(&[byte]);
+        self.write(&count.to_le_bytes());
+    }
+
+    /// Write a byte slice in reverse order, equivalent to writing a reversed
+    /// copy of it. The bytes are reversed in small stack-buffered chunks,
+    /// so no allocation is needed.
+    pub fn write_reversed(&mut self, bytes: &[u8]) {
+        let mut buf = [0u8; 256];
+        for chunk in bytes.rchunks(buf.len()) {
+            let out: &mut [u8] = &mut buf[..chunk.len()];
+            out.copy_from_slice(chunk);
+            out.reverse();
+            self.write(out);
+        }
+    }
+
+    /**
+    Write the raw bytes of any plain-old-data scalar (or array thereof)
+    into the hasher, without going through [Hash].
+
+    NOTE: the bytes are written in the **native** byte order, so digests
+    of multi-byte values differ between little- and big-endian targets.
+    Convert the value (e.g. with `to_le()`) first if portability matters.
+    */
+    #[cfg(feature = "bytemuck")]
+    #[inline]
+    pub fn write_scalar<T: bytemuck::Pod>(&mut self, value: T) {
+        self.write(bytemuck::bytes_of(&value));
+    }
+
+    /// Write `bytes[range]` into the hasher, returning an error instead of
+    /// panicking if the range is out of bounds (or reversed).
+    #[inline]
+    pub fn write_range(&mut self, bytes: &[u8], range: Range<usize>) -> Result<(), Xxh3Error> {
+        match bytes.get(range.clone()) {
+            Some(slice) => {
+                self.write(slice);
+                Ok(())
+            }
+            None => Err(Xxh3Error::InvalidRange {
+                start: range.start,
+                end: range.end,
+                len: bytes.len(),
+            }),
+        }
+    }
+
+    /**
+    Hash an [Option] with explicit, framing-stable tagging: a single `0`
+    byte for `None`, or a `1` byte followed by the value for `Some`.
+
+    The derived [Hash] does something similar, but its discriminant
+    encoding is not a documented, stable format. This one is, which makes
+    it suitable for cross-language compatible digests.
+    */
+    pub fn hash_option<T: Hash>(&mut self, opt: &Option<T>) {
+        match opt {
+            None => self.write(&[0]),
+            Some(value) => {
+                self.write(&[1]);
+                value.hash(self);
+            }
+        }
+    }
+
+    /// Hash a [Result] with explicit tagging, mirroring
+    /// [hash_option](Self::hash_option): a `0` byte followed by the value for
+    /// `Ok`, or a `1` byte followed by the error for `Err`. `Ok(x)` and
+    /// `Err(x)` are thus distinguished even when `T` and `E` overlap.
+    pub fn hash_result<T: Hash, E: Hash>(&mut self, r: &Result<T, E>) {
+        match r {
+            Ok(value) => {
+                self.write(&[0]);
+                value.hash(self);
+            }
+            Err(err) => {
+                self.write(&[1]);
+                err.hash(self);
+            }
         }
     }
 
@@ -170,18 +879,57 @@ impl CustomXxh3Hasher {
 impl Default for CustomXxh3Hasher {
     /// A [CustomXxh3Hasher] with the default seed (0) and secret [XXH3_SECRET].
     fn default() -> Self {
-        Self {
-            xxh: build_xxh3_with_secret(XXH3_SECRET),
-            seed: 0,
-            custom_secret: None,
-        }
+        Self::from_xxh3(build_xxh3_with_secret(XXH3_SECRET), 0, None)
+    }
+}
+
+impl From<u64> for CustomXxh3Hasher {
+    /// Shortcut for [CustomXxh3Hasher::new] with the given seed.
+    fn from(seed: u64) -> Self {
+        Self::new(seed)
+    }
+}
+
+impl PartialEq<u64> for CustomXxh3Hasher {
+    /// Compare the *current digest* (i.e. [finish](Hasher::finish)) with a
+    /// value, e.g. `assert_eq!(hasher, digest)` in tests. This reflects the
+    /// data written so far, not the hasher's configuration.
+    fn eq(&self, other: &u64) -> bool {
+        self.finish() == *other
     }
 }
 
+/// Implement primitive integer [Hasher] `write_*` methods honoring the
+/// [Endian] policy. The signed variants delegate to these by default.
+macro_rules! write_with_endianness {
+    ($($name:ident: $t:ty),+ $(,)?) => {$(
+        #[inline]
+        fn $name(&mut self, i: $t) {
+            match self.endian {
+                Endian::Native => self.write(&i.to_ne_bytes()),
+                Endian::Little => self.write(&i.to_le_bytes()),
+                Endian::Big => self.write(&i.to_be_bytes()),
+            }
+        }
+    )+};
+}
+
 impl Hasher for CustomXxh3Hasher {
     #[inline]
     fn write(&mut self, bytes: &[u8]) {
         self.xxh.write(bytes);
+        self.bytes_written += bytes.len() as u64;
+        if let Some(state) = &mut self.checkpoint {
+            state.update(bytes);
+        }
+    }
+
+    write_with_endianness! {
+        write_u16: u16,
+        write_u32: u32,
+        write_u64: u64,
+        write_u128: u128,
+        write_usize: usize,
     }
 
     /**
@@ -206,6 +954,26 @@ impl BuildHasher for CustomXxh3Hasher {
     }
 }
 
+/// A [hashbrown::HashMap] using [CustomXxh3Hasher] as its hash builder.
+#[cfg(feature = "hashbrown")]
+pub type Xxh3HashMap<K, V> = hashbrown::HashMap<K, V, CustomXxh3Hasher>;
+
+/// A [hashbrown::HashSet] using [CustomXxh3Hasher] as its hash builder.
+#[cfg(feature = "hashbrown")]
+pub type Xxh3HashSet<T> = hashbrown::HashSet<T, CustomXxh3Hasher>;
+
+/// Create an empty [Xxh3HashMap] with the given initial capacity.
+#[cfg(feature = "hashbrown")]
+pub fn xxh3_hashmap_with_capacity<K, V>(capacity: usize) -> Xxh3HashMap<K, V> {
+    hashbrown::HashMap::with_capacity_and_hasher(capacity, CustomXxh3Hasher::default())
+}
+
+/// Create an empty [Xxh3HashSet] with the given initial capacity.
+#[cfg(feature = "hashbrown")]
+pub fn xxh3_hashset_with_capacity<T>(capacity: usize) -> Xxh3HashSet<T> {
+    hashbrown::HashSet::with_capacity_and_hasher(capacity, CustomXxh3Hasher::default())
+}
+
 /* --------------------------------- */
 
 impl Deref for CustomXxh3Hasher {
@@ -241,6 +1009,12 @@ impl SizeOf for CustomXxh3Hasher {
         context
             .add(size_of::<CustomXxh3Hasher>())
             .add_distinct_allocation();
+        if self.prime.capacity() != 0 {
+            context.add(self.prime.capacity()).add_distinct_allocation();
+        }
+        if self.checkpoint.is_some() {
+            context.add(size_of::<Xxh3>()).add_distinct_allocation();
+        }
     }
 }
 
@@ -278,32 +1052,108 @@ impl<T: Hash + Xxh3Hashable> Hash for Xxh3Wrapper<T> {
     }
 }
 
-/* --------------------------------- */
+/**
+A trait for types which hash within their own domain, e.g. plugin types
+that could otherwise hash similar data to colliding digests.
 
-/// Add randomized state initialization similar to SipHash
-pub struct RandomXxh3Builder(RandomState);
+See [domain_digest] for hashing a value within its domain.
+*/
+pub trait HashDomain {
+    /// The domain tag mixed in before the value itself.
+    const DOMAIN_TAG: &'static [u8];
+}
 
-impl RandomXxh3Builder {
-    pub fn new() -> Self {
-        Self(RandomState::new())
-    }
+/**
+A trait for types with separate "identity" and "content" digests, e.g. a
+cache entry identified by its id but versioned by its content.
 
-    pub fn build_hasher(&self) -> CustomXxh3Hasher {
-        // Use the RandomState to generate a seed
-        let seed = {
-            let mut hasher = self.0.build_hasher();
-            hasher.write(&[0; 64]); // Some input to hash
-            hasher.finish()
-        };
-        CustomXxh3Hasher::new(seed)
+Implementors which can compute both digests in a single traversal should
+override [digests](Self::digests), which by default calls both methods.
+*/
+pub trait MultiDigest {
+    /// The digest of whatever identifies this item.
+    fn identity_digest(&self) -> u64;
+    /// The digest of the content of this item.
+    fn content_digest(&self) -> u64;
+
+    /// Both digests, as `(identity, content)`.
+    fn digests(&self) -> (u64, u64) {
+        (self.identity_digest(), self.content_digest())
     }
 }
 
-impl Default for RandomXxh3Builder {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+/**
+An extension trait for hashing all items of an iterator with [Xxh3].
+
+Items are fed in iteration order through a default [CustomXxh3Hasher],
+so the result equals [hash_iter] over the same items.
+
+Example:
+```ignore
+let digest = (0..100).xxh3_digest();
+```
+*/
+pub trait Xxh3IterExt {
+    /// Consume the iterator and return the digest of all its items.
+    fn xxh3_digest(self) -> u64;
+}
+
+impl<I> Xxh3IterExt for I
+where
+    I: Iterator,
+    I::Item: Hash,
+{
+    #[inline]
+    fn xxh3_digest(self) -> u64 {
+        self.fold(CustomXxh3Hasher::default(), |mut hasher, item| {
+            item.hash(&mut hasher);
+            hasher
+        })
+        .finish()
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl Xxh3Hashable for uuid::Uuid {
+    /// Feeds the 16 raw bytes of the UUID into the hasher.
+    #[inline]
+    fn xxh3<H: Hasher>(&self, state: &mut H) {
+        state.write(self.as_bytes());
+    }
+
+    #[inline]
+    fn xxh3_digest(&self) -> u64 {
+        hash_uuid(self)
+    }
+}
+
+/* --------------------------------- */
+
+/// Add randomized state initialization similar to SipHash
+#[derive(Clone)]
+pub struct RandomXxh3Builder(RandomState);
+
+impl RandomXxh3Builder {
+    pub fn new() -> Self {
+        Self(RandomState::new())
+    }
+
+    pub fn build_hasher(&self) -> CustomXxh3Hasher {
+        // Use the RandomState to generate a seed
+        let seed = {
+            let mut hasher = self.0.build_hasher();
+            hasher.write(&[0; 64]); // Some input to hash
+            hasher.finish()
+        };
+        CustomXxh3Hasher::new(seed)
+    }
+}
+
+impl Default for RandomXxh3Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 // Allow using this as a BuildHasher for HashMap
 impl BuildHasher for RandomXxh3Builder {
@@ -316,6 +1166,69 @@ impl BuildHasher for RandomXxh3Builder {
 
 /* --------------------------------- */
 
+/**
+A [BuildHasher] for testing how downstream code handles hash collisions:
+all digests are masked to their lowest `bits` bits, forcing every key into
+one of at most `2^bits` distinct digests (and hence collision chains).
+
+Only available with the `testing` feature. Never use this in production.
+*/
+#[cfg(feature = "testing")]
+#[derive(Clone, Copy, Debug)]
+pub struct CollidingXxh3Builder {
+    mask: u64,
+}
+
+#[cfg(feature = "testing")]
+impl CollidingXxh3Builder {
+    /// Create a new builder masking digests to `bits` bits (64 or more
+    /// leaves the digests intact, 0 makes every key collide).
+    pub fn new(bits: u32) -> Self {
+        let mask: u64 = match bits {
+            0 => 0,
+            1..=63 => u64::MAX >> (64 - bits),
+            _ => u64::MAX,
+        };
+        Self { mask }
+    }
+}
+
+#[cfg(feature = "testing")]
+impl BuildHasher for CollidingXxh3Builder {
+    type Hasher = CollidingXxh3Hasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        CollidingXxh3Hasher {
+            inner: CustomXxh3Hasher::default(),
+            mask: self.mask,
+        }
+    }
+}
+
+/// The [Hasher] built by [CollidingXxh3Builder].
+#[cfg(feature = "testing")]
+#[derive(Clone, Debug)]
+pub struct CollidingXxh3Hasher {
+    inner: CustomXxh3Hasher,
+    mask: u64,
+}
+
+#[cfg(feature = "testing")]
+impl Hasher for CollidingXxh3Hasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.write(bytes);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.inner
+            .finish_transformed(OutputTransform::Mask(self.mask))
+    }
+}
+
+/* --------------------------------- */
+
 pub trait Xxh3OptimizedHash {
     /// Provide specialized hashing for specific types
     fn hash_optimized<H: Hasher>(&self, state: &mut H);
@@ -329,99 +1242,2126 @@ impl CustomXxh3Hasher {
     }
 }
 
-/* ########################## UTILITY FUNCTIONS ############################ */
+/**
+A hasher template for many keys sharing a (long) common prefix.
 
-/// Hash a byte slice using [Xxh3] "oneshot" `xxh3_64_with_secret()` and a
-/// custom secret generated from constant [XXH3_SEED].
-#[inline]
-pub fn hash_bytes(bytes: &[u8]) -> u64 {
-    xxh3_64_with_secret(bytes, &XXH3_SECRET)
+The prefix is hashed once up front. Every [hash_suffix](Self::hash_suffix)
+call then forks the primed state and only hashes the suffix, without
+mutating the template. The result equals hashing `prefix ++ suffix` with a
+default [CustomXxh3Hasher] (or [hash_bytes]).
+*/
+#[derive(Clone, Debug)]
+pub struct PrefixedHasher {
+    template: CustomXxh3Hasher,
 }
 
-/// Hash a byte slice using [Xxh3] "oneshot" `xxh3_64()` and Xxh3 default seed.
-#[inline]
-pub fn hash_bytes_default(bytes: &[u8]) -> u64 {
-    xxh3_64(bytes)
+impl PrefixedHasher {
+    /// Create a new template with the given prefix already hashed.
+    pub fn new(prefix: &[u8]) -> Self {
+        let mut template: CustomXxh3Hasher = CustomXxh3Hasher::default();
+        template.write(prefix);
+        Self { template }
+    }
+
+    /// Get the digest of the prefix followed by `suffix`.
+    #[inline]
+    pub fn hash_suffix(&self, suffix: &[u8]) -> u64 {
+        let mut hasher: CustomXxh3Hasher = self.template.fork();
+        hasher.write(suffix);
+        hasher.finish()
+    }
 }
 
+/* --------------------------------- */
+
 /**
-A quick and dirty function to hash an item using [Xxh3] as the hasher.
-The item in question must implement the [Hash] trait, obviously.
+A [Hasher] decorator which XORs a per-instance salt into the first 8 bytes
+of every `write()` before forwarding it to a [CustomXxh3Hasher]. Two
+instances with different salts produce diverging digests for identical
+input streams, without having to manage secrets.
+
+NOTE: this is cheap obfuscation, **not** cryptography. The salt is easily
+recoverable from inputs and digests, and writes shorter than 8 bytes are
+only salted with the corresponding (little-endian) salt prefix.
+*/
+#[derive(Clone, Debug)]
+pub struct SaltedXxh3Hasher {
+    inner: CustomXxh3Hasher,
+    salt: [u8; 8],
+}
 
-NOTE: This function is not meant for high-performance use cases. It creates
-a new `Xxh3` for each call, which is not terribly efficient. Prefer building
-a single `Xxh3` instance with [CustomXxh3Hasher] for multiple hash calls, or
-use [hash_bytes] if the item can be represented as a byte slice.
+impl SaltedXxh3Hasher {
+    /// Create a new salted hasher around a default [CustomXxh3Hasher].
+    pub fn new(salt: u64) -> Self {
+        Self::with_hasher(CustomXxh3Hasher::default(), salt)
+    }
+
+    /// Create a new salted hasher around the given [CustomXxh3Hasher].
+    pub fn with_hasher(inner: CustomXxh3Hasher, salt: u64) -> Self {
+        Self {
+            inner,
+            salt: salt.to_le_bytes(),
+        }
+    }
+}
+
+impl Hasher for SaltedXxh3Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let n: usize = bytes.len().min(8);
+        let mut head = [0u8; 8];
+        for (i, h) in head[..n].iter_mut().enumerate() {
+            *h = bytes[i] ^ self.salt[i];
+        }
+        self.inner.write(&head[..n]);
+        self.inner.write(&bytes[n..]);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.inner.finish()
+    }
+}
+
+/* --------------------------------- */
+
+/**
+A streaming wrapper around [CustomXxh3Hasher] which yields the running
+digest at each chunk boundary, e.g. for per-chunk checkpoints in a chunked
+protocol.
+
+NOTE: the returned digests are *cumulative* (covering all chunks written
+so far), not independent per-chunk digests. Each one is simply the
+[finish](Hasher::finish) value after the chunk has been written.
 */
-#[inline]
-pub fn hash_item<T>(item: &T) -> u64
-where
-    T: Hash,
-{
-    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
-    item.hash(&mut hasher);
-    hasher.finish()
+#[derive(Clone, Debug, Default)]
+pub struct ProgressiveHasher {
+    inner: CustomXxh3Hasher,
 }
 
-/// Validate the secret size for [CustomXxh3Hasher]
-#[inline]
-fn validate_secret_size(secret: &[u8]) -> Option<Result<CustomXxh3Hasher, Xxh3Error>> {
-    if secret.len() != XXH3_SECRET_SIZE {
-        return Some(Err(Xxh3Error::InvalidSecretSize(secret.len())));
+impl ProgressiveHasher {
+    /// Create a new progressive hasher around a default [CustomXxh3Hasher].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new progressive hasher around the given [CustomXxh3Hasher].
+    pub fn with_hasher(inner: CustomXxh3Hasher) -> Self {
+        Self { inner }
+    }
+
+    /// Write a chunk and return the cumulative digest of all chunks so far.
+    #[inline]
+    pub fn write_chunk(&mut self, bytes: &[u8]) -> u64 {
+        self.inner.write(bytes);
+        self.inner.finish()
+    }
+
+    /// Consume the wrapper, returning the underlying hasher.
+    pub fn into_inner(self) -> CustomXxh3Hasher {
+        self.inner
     }
-    None
 }
 
-/* ######################################################################### */
+/* --------------------------------- */
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/**
+A schema-aware hasher for structured records: every field is hashed
+together with its name, so renaming, adding or removing a field in a future
+record layout changes the digest.
+
+Each [field](Self::field) is framed as the name length (little-endian
+`u64`), the name bytes, the value length (little-endian `u64`) and the
+value bytes. Fields are hashed in call order, so the declared field order
+is part of the schema: reordering `field` calls changes the digest.
+*/
+#[derive(Clone, Debug, Default)]
+pub struct RecordHasher {
+    inner: CustomXxh3Hasher,
+}
 
-    const TEST_DATA: &[u8] = b"Hello, world!";
+impl RecordHasher {
+    /// Create a new record hasher around a default [CustomXxh3Hasher].
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-    #[test]
-    fn test_default_hash_stability() {
-        let mut hasher1 = CustomXxh3Hasher::new_xxh3_defaults();
-        let mut hasher2 = CustomXxh3Hasher::new_xxh3_defaults();
+    /// Create a new record hasher around the given [CustomXxh3Hasher].
+    pub fn with_hasher(inner: CustomXxh3Hasher) -> Self {
+        Self { inner }
+    }
 
-        hasher1.write(&TEST_DATA);
-        hasher2.write(&TEST_DATA);
+    /// Hash a named field value.
+    #[inline]
+    pub fn field(&mut self, name: &str, value: &[u8]) -> &mut Self {
+        self.inner.write_framed(name.as_bytes());
+        self.inner.write_framed(value);
+        self
+    }
 
-        assert_eq!(
-            hasher1.finish(),
-            hasher2.finish(),
-            "Default XXH3 hashes should match"
-        );
+    /// Get the digest of the fields hashed so far.
+    #[inline]
+    pub fn finish(&self) -> u64 {
+        self.inner.finish()
     }
+}
 
-    #[test]
-    fn test_custom_hash_stability() {
-        let mut hasher1 = CustomXxh3Hasher::default();
-        let mut hasher2 = CustomXxh3Hasher::default();
+/* --------------------------------- */
 
-        hasher1.write(&TEST_DATA);
-        hasher2.write(&TEST_DATA);
+/// Adler-32 modulus: the largest prime below 2^16.
+const ADLER_MOD: u32 = 65521;
 
-        assert_eq!(
-            hasher1.finish(),
-            hasher2.finish(),
-            "Custom XXH3 hashes should match"
-        );
+/**
+An Adler-32 style running combine of per-chunk digests (e.g. [hash_bytes]
+of each chunk), for interop with legacy protocols combining chunk hashes
+this way.
+
+Starting from `a = 1, b = 0`, every chunk digest `h` is folded in as:
+
+```text
+a = (a + h mod 65521) mod 65521
+b = (b + a) mod 65521
+```
+
+and the combined value is `(b << 16) | a`. The combine is order-sensitive.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RollingCombine32 {
+    a: u32,
+    b: u32,
+}
+
+impl RollingCombine32 {
+    /// Create a new combine in the initial `a = 1, b = 0` state.
+    pub const fn new() -> Self {
+        Self { a: 1, b: 0 }
     }
 
-    #[test]
-    fn test_random_hashes() {
-        let mut hasher1 = RandomXxh3Builder::new().build_hasher();
-        let mut hasher2 = RandomXxh3Builder::new().build_hasher();
+    /// Fold a chunk digest into the combine.
+    #[inline]
+    pub fn add(&mut self, chunk_hash: u64) {
+        let h: u32 = (chunk_hash % ADLER_MOD as u64) as u32;
+        self.a = (self.a + h) % ADLER_MOD;
+        self.b = (self.b + self.a) % ADLER_MOD;
+    }
 
-        hasher1.write(&TEST_DATA);
-        hasher2.write(&TEST_DATA);
+    /// Get the combined 32-bit value, `(b << 16) | a`.
+    #[inline]
+    pub const fn combined(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
 
-        assert_ne!(
-            hasher1.finish(),
-            hasher2.finish(),
-            "Random hashes should differ"
-        );
+impl Default for RollingCombine32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/* --------------------------------- */
+
+/**
+An object-safe hashing interface, e.g. for storing hashers as
+`Box<dyn DynHasher>` in a plugin registry. Unlike [Hasher], it has no
+generic methods and doesn't require callers to know the concrete type.
+*/
+pub trait DynHasher {
+    /// Feed bytes into the hasher.
+    fn update(&mut self, bytes: &[u8]);
+    /// Get the digest of the bytes fed so far.
+    fn digest(&self) -> u64;
+}
+
+/// A [CustomXxh3Hasher] wrapper implementing the object-safe [DynHasher].
+#[derive(Clone, Debug, Default)]
+pub struct DynXxh3 {
+    inner: CustomXxh3Hasher,
+}
+
+impl DynXxh3 {
+    /// Create a new wrapper around a default [CustomXxh3Hasher].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new wrapper around the given [CustomXxh3Hasher].
+    pub fn with_hasher(inner: CustomXxh3Hasher) -> Self {
+        Self { inner }
+    }
+
+    /// Box this wrapper as a [DynHasher] trait object.
+    pub fn boxed(self) -> Box<dyn DynHasher> {
+        Box::new(self)
+    }
+}
+
+impl DynHasher for DynXxh3 {
+    #[inline]
+    fn update(&mut self, bytes: &[u8]) {
+        self.inner.write(bytes);
+    }
+
+    #[inline]
+    fn digest(&self) -> u64 {
+        self.inner.finish()
+    }
+}
+
+/* --------------------------------- */
+
+/**
+A [Hasher] which hashes normally with a [CustomXxh3Hasher], but also keeps
+a 256-entry byte frequency table of everything written, e.g. to estimate
+the entropy of data (and decide whether it's worth compressing) in the same
+pass as hashing it.
+*/
+#[derive(Clone, Debug)]
+pub struct HistogramHasher {
+    inner: CustomXxh3Hasher,
+    histogram: [u64; 256],
+}
+
+impl HistogramHasher {
+    /// Create a new histogram hasher around a default [CustomXxh3Hasher].
+    pub fn new() -> Self {
+        Self::with_hasher(CustomXxh3Hasher::default())
+    }
+
+    /// Create a new histogram hasher around the given [CustomXxh3Hasher].
+    pub fn with_hasher(inner: CustomXxh3Hasher) -> Self {
+        Self {
+            inner,
+            histogram: [0; 256],
+        }
+    }
+
+    /// Get the digest of all bytes written so far.
+    #[inline]
+    pub fn digest(&self) -> u64 {
+        self.inner.finish()
+    }
+
+    /// Get the number of occurrences of every byte value written so far.
+    pub fn byte_histogram(&self) -> [u64; 256] {
+        self.histogram
+    }
+}
+
+impl Default for HistogramHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for HistogramHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.histogram[b as usize] += 1;
+        }
+        self.inner.write(bytes);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.inner.finish()
+    }
+}
+
+/* --------------------------------- */
+
+/**
+A hasher for append-only logs, tracking two digests at once so that
+"entries were reordered" can be told apart from "entry content changed":
+
+- the [content digest](Self::content_digest) covers all entries in order,
+  each framed with its length (see [CustomXxh3Hasher::write_framed]);
+- the [order-independent digest](Self::order_independent_digest) is the
+  XOR of the [hash_bytes] digests of all entries.
+
+If only the content digest differs, the same entries were appended in a
+different order. NOTE: identical entry pairs cancel out of the XOR.
+*/
+#[derive(Clone, Debug, Default)]
+pub struct LogHasher {
+    content: CustomXxh3Hasher,
+    xor: u64,
+}
+
+impl LogHasher {
+    /// Create a new, empty log hasher.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a log entry.
+    pub fn append(&mut self, entry: &[u8]) {
+        self.content.write_framed(entry);
+        self.xor ^= hash_bytes(entry);
+    }
+
+    /// Get the order-sensitive digest of all entries appended so far.
+    #[inline]
+    pub fn content_digest(&self) -> u64 {
+        self.content.finish()
+    }
+
+    /// Get the XOR of the digests of all entries appended so far.
+    #[inline]
+    pub fn order_independent_digest(&self) -> u64 {
+        self.xor
+    }
+}
+
+/* ########################## UTILITY FUNCTIONS ############################ */
+
+/// Hash a byte slice using [Xxh3] "oneshot" `xxh3_64_with_secret()` and a
+/// custom secret generated from constant [XXH3_SEED].
+#[inline]
+pub fn hash_bytes(bytes: &[u8]) -> u64 {
+    xxh3_64_with_secret(bytes, &XXH3_SECRET)
+}
+
+/// Hash a byte slice using [Xxh3] "oneshot" `xxh3_128_with_secret()` and a
+/// custom secret generated from constant [XXH3_SECRET_SEED].
+#[inline]
+pub fn hash_bytes_128(bytes: &[u8]) -> u128 {
+    xxh3_128_with_secret(bytes, &XXH3_SECRET)
+}
+
+/**
+Rehash a digest into a new domain: hash its 8 little-endian bytes under the
+given secret, e.g. for deriving per-shard keys from a base digest.
+
+The secret must be exactly [XXH3_SECRET_SIZE] bytes, otherwise
+[Xxh3Error::InvalidSecretSize] is returned.
+*/
+pub fn rehash(digest: u64, secret: &[u8]) -> Result<u64, Xxh3Error> {
+    if secret.len() != XXH3_SECRET_SIZE {
+        return Err(Xxh3Error::InvalidSecretSize(secret.len()));
+    }
+    Ok(xxh3_64_with_secret(&digest.to_le_bytes(), secret))
+}
+
+/// Rehash a 128-bit digest into a new domain: like [rehash], but hashes the
+/// 16 little-endian bytes of the digest to a 128-bit result.
+pub fn rehash_128(digest: u128, secret: &[u8]) -> Result<u128, Xxh3Error> {
+    if secret.len() != XXH3_SECRET_SIZE {
+        return Err(Xxh3Error::InvalidSecretSize(secret.len()));
+    }
+    Ok(xxh3_128_with_secret(&digest.to_le_bytes(), secret))
+}
+
+/**
+Hash a string char by char: every [char] is written as its little-endian
+`u32` code point (see [CustomXxh3Hasher::write_char]).
+
+This differs from hashing the UTF-8 bytes (e.g. with [hash_bytes]): every
+char takes 4 bytes regardless of its UTF-8 length, so the digests are not
+interchangeable, but they match those of e.g. UTF-32 encoded text.
+*/
+pub fn hash_str_chars(s: &str) -> u64 {
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    for c in s.chars() {
+        hasher.write_char(c);
+    }
+    hasher.finish()
+}
+
+/**
+Hash a byte slice with [hash_bytes] and map the digest to a fraction in
+`[0, 1)`, e.g. for deterministic sampling ("keep 1% of keys").
+
+The top 53 bits of the digest are scaled by `2^-53`, so the result is
+exactly representable and never reaches 1.0. The fractions are only as
+uniform as the digests are, which holds well for xxHash3 over distinct
+inputs, but isn't a guarantee against adversarially chosen keys.
+*/
+#[inline]
+pub fn hash_bytes_fraction(bytes: &[u8]) -> f64 {
+    digest_fraction(hash_bytes(bytes))
+}
+
+/**
+Deterministically sample buffers, e.g. to pick representative samples for
+compression dictionary training, returning the digests of the selected
+buffers in input order.
+
+A buffer is selected if its [hash_bytes_fraction] is below
+`sample_fraction`, so roughly that fraction of (distinct) buffers is kept,
+and the same buffer is always either kept or skipped. Identical buffers
+are all selected or all skipped together.
+*/
+pub fn sample_digests(buffers: &[&[u8]], sample_fraction: f64) -> Vec<u64> {
+    buffers
+        .iter()
+        .map(|buf| hash_bytes(buf))
+        .filter(|digest| digest_fraction(*digest) < sample_fraction)
+        .collect()
+}
+
+/**
+Hash a batch of fixed-size keys (e.g. 16-byte ids) with [hash_bytes],
+returning their digests in order.
+
+The keys are read straight from the caller's contiguous `[[u8; N]]` slice,
+a sequential access pattern which hardware prefetchers already handle well,
+and every key takes the oneshot path for its (compile-time) size class. No
+explicit blocking or software prefetching is done. `benches/fixed_keys.rs`
+compares this against hashing in blocks of 64 keys while prefetching the
+next block, for several key and batch sizes; run it with
+`cargo bench --bench fixed_keys` to measure either on your target.
+*/
+pub fn hash_fixed_keys<const N: usize>(keys: &[[u8; N]]) -> Vec<u64> {
+    keys.iter().map(|key| hash_bytes(key)).collect()
+}
+
+/// Build a `(length, digest)` dedup key for a byte slice. Content of
+/// different lengths can never share a key, which cuts collisions among
+/// e.g. files sharing a common prefix.
+#[inline]
+pub fn dedup_key(bytes: &[u8]) -> (u64, u64) {
+    (bytes.len() as u64, hash_bytes(bytes))
+}
+
+/**
+Cheaply pre-check whether two byte slices differ, before a full comparison.
+
+Returns `true` if their lengths or [hash_bytes_128] digests differ, in
+which case the slices definitely differ. Returns `false` if the digests
+match: the slices are then equal, barring a 128-bit digest collision
+(negligibly unlikely, but not impossible, so confirm if it matters).
+*/
+pub fn quick_differ(a: &[u8], b: &[u8]) -> bool {
+    a.len() != b.len() || hash_bytes_128(a) != hash_bytes_128(b)
+}
+
+/**
+Scan buffers for duplicates by their [hash_bytes_128] digests, stopping at
+the first one found. Returns the indices `(first, duplicate)` of the
+earliest buffer whose digest was already seen, or `None` if all differ.
+
+The buffers themselves aren't compared, so a reported pair could in theory
+be a digest collision rather than a real duplicate. With 128-bit digests
+the odds are negligible (see [collision_probability]).
+*/
+pub fn hash_until_duplicate(buffers: &[&[u8]]) -> Option<(usize, usize)> {
+    let mut seen: std::collections::HashMap<u128, usize> =
+        std::collections::HashMap::with_capacity(buffers.len());
+    for (idx, buf) in buffers.iter().enumerate() {
+        if let Some(first) = seen.insert(hash_bytes_128(buf), idx) {
+            return Some((first, idx));
+        }
+    }
+    None
+}
+
+/// Hash a byte slice using [Xxh3] "oneshot" `xxh3_64()` and Xxh3 default seed.
+#[inline]
+pub fn hash_bytes_default(bytes: &[u8]) -> u64 {
+    xxh3_64(bytes)
+}
+
+/// The digest of empty input (zero bytes) with our custom [XXH3_SECRET].
+/// Equals `hash_bytes(b"")`, which makes it usable as a sentinel value.
+#[inline]
+pub const fn hash_empty() -> u64 {
+    const_xxh3_64_with_secret(&[], &XXH3_SECRET)
+}
+
+/// Hash the native-endian bytes of a `u64` with our custom [XXH3_SECRET] in
+/// `const` context. Equals [hash_bytes] of `v.to_ne_bytes()`, so precomputed
+/// digests can be used e.g. as `match` arms.
+#[inline]
+pub const fn const_hash_u64(v: u64) -> u64 {
+    const_xxh3_64_with_secret(&v.to_ne_bytes(), &XXH3_SECRET)
+}
+
+/// Hash the UTF-8 bytes of a string with our custom [XXH3_SECRET] in `const`
+/// context. Equals [hash_bytes] of `s.as_bytes()`. See also [const_str_hash].
+#[inline]
+pub const fn const_hash_str(s: &str) -> u64 {
+    const_xxh3_64_with_secret(s.as_bytes(), &XXH3_SECRET)
+}
+
+/**
+Hash a string literal at compile time, e.g. for `const` route/tag dispatch.
+
+Expands to a `u64` evaluated in `const` context via [const_hash_str], so the
+result is usable in `const` items and `match` guards, and equals the runtime
+[hash_bytes] of the literal's bytes.
+
+```
+use custom_xxh3::{const_str_hash, hash_bytes};
+
+const ROUTE_INDEX: u64 = const_str_hash!("/index");
+assert_eq!(ROUTE_INDEX, hash_bytes(b"/index"));
+```
+*/
+#[macro_export]
+macro_rules! const_str_hash {
+    ($s:literal) => {{
+        const DIGEST: u64 = $crate::const_hash_str($s);
+        DIGEST
+    }};
+}
+
+/**
+Implement [Xxh3Hashable] for a type by hashing the listed fields in the
+given order, without a proc-macro derive.
+
+Syntax: `impl_xxh3_hashable!(Type { field, ... });` where each field is a
+named field or a tuple index of `Type`, and must implement [Hash]. Like the
+`hash_tupleN` functions, every field is hashed into its own [hash_item]
+digest, and the digests are written in order as little-endian `u64`s, so
+field boundaries can't shift. Generic types are not supported.
+
+```
+use custom_xxh3::{impl_xxh3_hashable, Xxh3Hashable};
+
+struct Point {
+    x: i32,
+    y: i32,
+    label: String,
+}
+impl_xxh3_hashable!(Point { x, y, label });
+
+let p = Point { x: 1, y: 2, label: "origin".into() };
+assert_eq!(p.xxh3_digest(), p.xxh3_digest());
+```
+*/
+#[macro_export]
+macro_rules! impl_xxh3_hashable {
+    ($t:ty { $($field:tt),+ $(,)? }) => {
+        impl $crate::Xxh3Hashable for $t {
+            fn xxh3<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                $(state.write(&$crate::hash_item(&self.$field).to_le_bytes());)+
+            }
+
+            fn xxh3_digest(&self) -> u64 {
+                let mut hasher = $crate::CustomXxh3Hasher::default();
+                $crate::Xxh3Hashable::xxh3(self, &mut hasher);
+                ::core::hash::Hasher::finish(&hasher)
+            }
+        }
+    };
+}
+
+/// Hash the 16 raw bytes of a [uuid::Uuid] with [hash_bytes]. This is
+/// cheaper than going through the generic [Hash] path.
+#[cfg(feature = "uuid")]
+#[inline]
+pub fn hash_uuid(u: &uuid::Uuid) -> u64 {
+    hash_bytes(u.as_bytes())
+}
+
+/**
+A quick and dirty function to hash an item using [Xxh3] as the hasher.
+The item in question must implement the [Hash] trait, obviously.
+
+NOTE: This function is not meant for high-performance use cases. It creates
+a new `Xxh3` for each call, which is not terribly efficient. Prefer building
+a single `Xxh3` instance with [CustomXxh3Hasher] for multiple hash calls, or
+use [hash_bytes] if the item can be represented as a byte slice.
+
+The hasher lives on the stack and has no prime or checkpoint, so this
+doesn't allocate (unless the item's [Hash] impl does).
+*/
+#[inline]
+pub fn hash_item<T>(item: &T) -> u64
+where
+    T: Hash,
+{
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    item.hash(&mut hasher);
+    hasher.finish()
+}
+
+/**
+Hash an item without touching the heap. This is [hash_item] under a name
+stating that guarantee, which `tests/no_alloc.rs` checks for both.
+
+NOTE: the [Xxh3] state is several hundred bytes, so this is meant for
+hashing small items where heap allocations must be avoided entirely.
+*/
+#[inline]
+pub fn hash_item_stack<T: Hash>(item: &T) -> u64 {
+    hash_item(item)
+}
+
+/**
+Deterministically combine the digests of two consecutive parts of an ordered
+stream (e.g. hashed by two parallel workers) into a single digest.
+
+The digests and the length of the second part are framed as little-endian
+`u64`s and hashed with [hash_bytes]. The combination is order-sensitive.
+
+NOTE: this is a distinct scheme, **not** byte-exact `xxHash3` of the whole
+stream, since `xxHash3` doesn't support concatenating streaming states.
+*/
+pub fn combine_ordered(first: u64, second: u64, second_len: u64) -> u64 {
+    let mut buf = [0u8; 24];
+    buf[..8].copy_from_slice(&first.to_le_bytes());
+    buf[8..16].copy_from_slice(&second.to_le_bytes());
+    buf[16..].copy_from_slice(&second_len.to_le_bytes());
+    hash_bytes(&buf)
+}
+
+/// Derive a seed for a reproducible, data-driven shuffle (see
+/// [shuffle_in_place]). This is simply [hash_bytes] of the data.
+#[inline]
+pub fn shuffle_seed(data: &[u8]) -> u64 {
+    hash_bytes(data)
+}
+
+/**
+Shuffle a slice in place (Fisher-Yates) reproducibly from a seed, e.g. one
+derived with [shuffle_seed].
+
+The random numbers come from a SplitMix64 generator seeded with `seed`,
+mapped to index ranges with a 128-bit multiply (Lemire's method) instead of
+a modulo. The same seed always yields the same permutation.
+*/
+pub fn shuffle_in_place<T>(slice: &mut [T], seed: u64) {
+    let mut state: u64 = seed;
+    for i in (1..slice.len()).rev() {
+        let r: u64 = splitmix64(&mut state);
+        let j = ((r as u128 * (i as u128 + 1)) >> 64) as usize;
+        slice.swap(i, j);
+    }
+}
+
+/**
+Hash a byte slice with digest semantics guaranteed not to depend on which
+optional features are enabled: a thin wrapper over [hash_bytes], whose
+output is pinned by known-answer tests. A matrix test re-runs those in a
+child `cargo test` without features, with each feature alone and with
+`--all-features`, as part of every plain `cargo test`. Use this for
+digests that must survive feature flag changes.
+*/
+#[inline]
+pub fn feature_invariant_digest(bytes: &[u8]) -> u64 {
+    hash_bytes(bytes)
+}
+
+/**
+Hash a byte slice with [hash_bytes] and apply an extra SplitMix64
+finalization step to the digest, for tiny inputs used as table indices.
+
+NOTE: this is a distinct scheme, and its digests are not comparable with
+plain [hash_bytes] ones. XXH3 already finalizes 1-3 byte inputs with a full
+avalanche mix, and the extra step doesn't measurably improve on it: single
+byte inputs show the same ~32 bit mean Hamming distance between adjacent
+inputs, and the same bucket spread, either way. Prefer [hash_bytes] unless
+the distinct digests themselves are wanted.
+*/
+#[inline]
+pub fn hash_bytes_mixed(bytes: &[u8]) -> u64 {
+    let mut state: u64 = hash_bytes(bytes);
+    splitmix64(&mut state)
+}
+
+/**
+Derive a child seed from a parent seed and a child index, for hierarchical
+seeding: a tree of independent hash domains from a single root seed.
+
+The child seed is [hash_bytes] of the parent followed by the index (both
+as little-endian `u64`s). It is fully deterministic: the same pair always
+yields the same seed, across runs and platforms.
+*/
+pub fn derive_seed(parent: u64, index: u64) -> u64 {
+    let mut buf = [0u8; 16];
+    buf[..8].copy_from_slice(&parent.to_le_bytes());
+    buf[8..].copy_from_slice(&index.to_le_bytes());
+    hash_bytes(&buf)
+}
+
+/// Advance a SplitMix64 state and return the next output.
+#[inline]
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z: u64 = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Hash a value within its [HashDomain]: the (length-framed) domain tag is
+/// written before the value into a default [CustomXxh3Hasher].
+pub fn domain_digest<T: HashDomain + Hash>(value: &T) -> u64 {
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    hasher.write_framed(T::DOMAIN_TAG);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/**
+Estimate the probability of at least one collision among `n_items` random
+digests of `bits` width (64 for [finish](Hasher::finish), 128 for
+[finish_128](CustomXxh3Hasher::finish_128)), using the birthday bound
+`p = 1 - exp(-n * (n - 1) / 2^(bits + 1))`.
+
+Useful e.g. when deciding whether 64 bits are enough for a dataset.
+*/
+pub fn collision_probability(n_items: u64, bits: u32) -> f64 {
+    let n = n_items as f64;
+    let x: f64 = n * (n - 1.0) / 2f64.powi(bits as i32 + 1);
+    -(-x).exp_m1()
+}
+
+/// Hash all items of an iterator in order using a default [CustomXxh3Hasher].
+#[inline]
+pub fn hash_iter<I>(items: I) -> u64
+where
+    I: IntoIterator,
+    I::Item: Hash,
+{
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    for item in items {
+        item.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/**
+Get the name of the xxHash3 backend in use: `"avx2"`, `"sse2"`, `"neon"`
+or `"scalar"`. Handy for explaining performance differences across machines
+in bug reports.
+
+NOTE: `xxhash_rust` doesn't expose its dispatch, nor does it detect CPU
+features at runtime; it picks the SIMD path at *compile time* from the
+enabled target features (e.g. `-C target-cpu=native`). This function
+mirrors that selection, so it reports what the binary was built for rather
+than what the running CPU could do.
+*/
+pub const fn active_dispatch() -> &'static str {
+    if cfg!(target_feature = "avx2") {
+        "avx2"
+    } else if cfg!(target_feature = "sse2") {
+        "sse2"
+    } else if cfg!(target_feature = "neon") {
+        "neon"
+    } else {
+        "scalar"
+    }
+}
+
+/// Map a digest to `[0, 1)` using its top 53 bits (the `f64` mantissa
+/// precision). Dividing by `u64::MAX` instead could round up to exactly 1.0.
+#[inline]
+fn digest_fraction(digest: u64) -> f64 {
+    (digest >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Remap a zero digest to `u64::MAX`, passing other digests through.
+#[inline]
+fn nonzero_digest(digest: u64) -> NonZeroU64 {
+    NonZeroU64::new(digest).unwrap_or(NonZeroU64::MAX)
+}
+
+/// Validate the secret size for [CustomXxh3Hasher]
+#[inline]
+fn validate_secret_size(secret: &[u8]) -> Option<Result<CustomXxh3Hasher, Xxh3Error>> {
+    if secret.len() != XXH3_SECRET_SIZE {
+        return Some(Err(Xxh3Error::InvalidSecretSize(secret.len())));
+    }
+    None
+}
+
+/* ######################################################################### */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_DATA: &[u8] = b"Hello, world!";
+
+    #[test]
+    fn test_default_hash_stability() {
+        let mut hasher1 = CustomXxh3Hasher::new_xxh3_defaults();
+        let mut hasher2 = CustomXxh3Hasher::new_xxh3_defaults();
+
+        hasher1.write(&TEST_DATA);
+        hasher2.write(&TEST_DATA);
+
+        assert_eq!(
+            hasher1.finish(),
+            hasher2.finish(),
+            "Default XXH3 hashes should match"
+        );
+    }
+
+    #[test]
+    fn test_custom_hash_stability() {
+        let mut hasher1 = CustomXxh3Hasher::default();
+        let mut hasher2 = CustomXxh3Hasher::default();
+
+        hasher1.write(&TEST_DATA);
+        hasher2.write(&TEST_DATA);
+
+        assert_eq!(
+            hasher1.finish(),
+            hasher2.finish(),
+            "Custom XXH3 hashes should match"
+        );
+    }
+
+    #[test]
+    fn test_random_hashes() {
+        let mut hasher1 = RandomXxh3Builder::new().build_hasher();
+        let mut hasher2 = RandomXxh3Builder::new().build_hasher();
+
+        hasher1.write(&TEST_DATA);
+        hasher2.write(&TEST_DATA);
+
+        assert_ne!(
+            hasher1.finish(),
+            hasher2.finish(),
+            "Random hashes should differ"
+        );
+    }
+
+    #[test]
+    fn test_from_u64_seed() {
+        let mut hasher1: CustomXxh3Hasher = 42.into();
+        let mut hasher2 = CustomXxh3Hasher::new(42);
+
+        hasher1.write(TEST_DATA);
+        hasher2.write(TEST_DATA);
+
+        assert_eq!(hasher1.seed(), 42);
+        assert_eq!(
+            hasher1.finish(),
+            hasher2.finish(),
+            "From<u64> should match new()"
+        );
+    }
+
+    #[test]
+    fn test_secret_toggling_keeps_config() {
+        let mut toggled = CustomXxh3Hasher::with_endianness(7, Endian::Big);
+        toggled.set_prime(b"prime");
+        let mut toggled = toggled.with_default_secret().without_secret();
+        let mut expected = CustomXxh3Hasher::with_endianness(7, Endian::Big);
+        expected.set_prime(b"prime");
+        expected.reset();
+
+        assert_eq!(toggled.prime(), b"prime");
+        toggled.write_u32(0xDEAD_BEEF);
+        expected.write_u32(0xDEAD_BEEF);
+        assert_eq!(
+            toggled.finish(),
+            expected.finish(),
+            "Prime and endianness should survive secret toggling"
+        );
+    }
+
+    #[test]
+    fn test_secret_toggling() {
+        let mut plain = CustomXxh3Hasher::new(7).without_secret();
+        let mut secret = CustomXxh3Hasher::new(7).with_default_secret();
+        let mut back = CustomXxh3Hasher::new(7)
+            .with_default_secret()
+            .without_secret();
+
+        // with a non-zero seed, Xxh3 only uses the secret for long inputs
+        let data = [0xA5u8; 1024];
+        plain.write(&data);
+        secret.write(&data);
+        back.write(&data);
+
+        assert_eq!(plain.seed(), 7);
+        assert_eq!(secret.seed(), 7);
+        assert_ne!(
+            plain.finish(),
+            secret.finish(),
+            "Toggling the secret should change the digest"
+        );
+        assert_eq!(
+            plain.finish(),
+            back.finish(),
+            "Removing the secret should restore Xxh3 defaults"
+        );
+    }
+
+    #[test]
+    fn test_const_hash_u64() {
+        const DIGEST: u64 = const_hash_u64(42);
+
+        assert_eq!(DIGEST, hash_bytes(&42u64.to_ne_bytes()));
+        assert_eq!(
+            const_hash_u64(u64::MAX),
+            hash_bytes(&u64::MAX.to_ne_bytes())
+        );
+    }
+
+    #[test]
+    fn test_iter_ext_digest() {
+        let items: Vec<u32> = (0..100).collect();
+
+        assert_eq!((0..100u32).xxh3_digest(), hash_iter(&items));
+        assert_eq!(
+            items.iter().xxh3_digest(),
+            CustomXxh3Hasher::default().hash_batch(&items)
+        );
+    }
+
+    #[test]
+    fn test_domain_separation() {
+        let mut hasher1 = CustomXxh3Hasher::with_domain(b"users", 0);
+        let mut hasher2 = CustomXxh3Hasher::with_domain(b"groups", 0);
+        let mut hasher3 = CustomXxh3Hasher::with_domain(b"users", 0);
+
+        hasher1.write(TEST_DATA);
+        hasher2.write(TEST_DATA);
+        hasher3.write(TEST_DATA);
+
+        assert_ne!(
+            hasher1.finish(),
+            hasher2.finish(),
+            "Different domains should produce different hashes"
+        );
+        assert_eq!(hasher1.finish(), hasher3.finish());
+    }
+
+    #[test]
+    fn test_reset_reapplies_prime() {
+        let mut primed = CustomXxh3Hasher::default();
+        primed.set_prime(b"prefix:");
+        primed.write(b"junk");
+        primed.reset();
+        primed.write(TEST_DATA);
+
+        let mut manual = CustomXxh3Hasher::default();
+        manual.write(b"prefix:");
+        manual.write(TEST_DATA);
+
+        assert_eq!(
+            primed.finish(),
+            manual.finish(),
+            "Prime should survive reset"
+        );
+
+        let mut domain = CustomXxh3Hasher::with_domain(b"users", 0);
+        let mut fresh = domain.clone();
+        domain.write(b"junk");
+        domain.reset();
+        domain.write(TEST_DATA);
+        fresh.write(TEST_DATA);
+
+        assert_eq!(
+            domain.finish(),
+            fresh.finish(),
+            "Domain should survive reset"
+        );
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_hash_uuid() {
+        let id = uuid::Uuid::from_u128(0x6f9619ff_8b86_d011_b42d_00c04fc964ff);
+
+        assert_eq!(hash_uuid(&id), hash_bytes(id.as_bytes()));
+        assert_eq!(id.xxh3_digest(), hash_bytes(id.as_bytes()));
+    }
+
+    #[test]
+    fn test_finish_summary() {
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write(TEST_DATA);
+        hasher.write_u32(7);
+
+        let summary = hasher.finish_summary();
+        assert_eq!(summary.bytes, TEST_DATA.len() as u64 + 4);
+        assert_eq!(summary.digest, hasher.finish());
+
+        hasher.reset();
+        assert_eq!(
+            hasher.finish_summary(),
+            HashSummary {
+                digest: hash_bytes(b""),
+                bytes: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_combine_ordered() {
+        let first = hash_bytes(b"Hello, ");
+        let second = hash_bytes(b"world!");
+
+        let combined = combine_ordered(first, second, 6);
+        assert_eq!(combined, combine_ordered(first, second, 6));
+        assert_ne!(combined, combine_ordered(second, first, 6));
+        assert_ne!(combined, combine_ordered(first, second, 7));
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn test_write_scalar() {
+        let value: f64 = std::f64::consts::PI;
+        let mut hasher1 = CustomXxh3Hasher::default();
+        let mut hasher2 = CustomXxh3Hasher::default();
+
+        hasher1.write_scalar(value);
+        hasher2.write(&value.to_ne_bytes());
+
+        assert_eq!(hasher1.finish(), hasher2.finish());
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn test_write_range() {
+        let mut hasher = CustomXxh3Hasher::default();
+        assert!(hasher.write_range(TEST_DATA, 7..12).is_ok());
+        assert_eq!(hasher.finish(), hash_bytes(b"world"));
+        assert_eq!(hasher.bytes_written(), 5);
+
+        assert!(matches!(
+            hasher.write_range(TEST_DATA, 7..20),
+            Err(Xxh3Error::InvalidRange {
+                start: 7,
+                end: 20,
+                len: 13
+            })
+        ));
+        assert!(hasher.write_range(TEST_DATA, 5..2).is_err());
+        assert_eq!(
+            hasher.bytes_written(),
+            5,
+            "Failed writes shouldn't hash anything"
+        );
+    }
+
+    #[test]
+    fn test_xxhsum_line() {
+        let mut hasher = CustomXxh3Hasher::new_xxh3_defaults();
+        assert_eq!(
+            hasher.finish_xxhsum_line("empty.txt"),
+            "XXH3_2d06800538d394c2  empty.txt"
+        );
+
+        hasher.write(TEST_DATA);
+        let line = hasher.finish_xxhsum_line("hello.txt");
+        assert_eq!(line, format!("XXH3_{:016x}  hello.txt", xxh3_64(TEST_DATA)));
+    }
+
+    #[test]
+    fn test_from_key() {
+        let hasher1 = CustomXxh3Hasher::from_key("users");
+        let hasher2 = CustomXxh3Hasher::from_key("users");
+        let hasher3 = CustomXxh3Hasher::from_key("groups");
+
+        assert_eq!(hasher1.seed(), hasher2.seed());
+        assert_eq!(hasher1.seed(), hash_bytes(b"users"));
+        assert_ne!(hasher1.seed(), hasher3.seed());
+
+        // the key has to matter past 240 bytes, where Xxh3 ignores the seed
+        for len in [16, 240, 241, 1024] {
+            let data = vec![0xA5u8; len];
+            let mut users = CustomXxh3Hasher::from_key("users");
+            let mut groups = CustomXxh3Hasher::from_key("groups");
+            users.write(&data);
+            groups.write(&data);
+            assert_ne!(
+                users.finish(),
+                groups.finish(),
+                "Same digest at {len} bytes"
+            );
+        }
+    }
+
+    #[test]
+    fn test_finish_128() {
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write(TEST_DATA);
+
+        assert_eq!(hasher.finish_128(), hash_bytes_128(TEST_DATA));
+    }
+
+    #[test]
+    fn test_from_config() {
+        let secret = [42u8; XXH3_SECRET_SIZE];
+        let mut hasher1 = CustomXxh3Hasher::from_config(Xxh3Config {
+            seed: 5,
+            secret: Some(secret),
+        });
+        let mut hasher2 = CustomXxh3Hasher::with_secret_and_seed(&secret, 5).unwrap();
+        let mut hasher3 = CustomXxh3Hasher::from_config(Xxh3Config::default());
+        let mut hasher4 = CustomXxh3Hasher::new(0);
+
+        for hasher in [&mut hasher1, &mut hasher2, &mut hasher3, &mut hasher4] {
+            hasher.write(TEST_DATA);
+        }
+
+        assert_eq!(hasher1.seed(), 5);
+        assert_eq!(hasher1.finish(), hasher2.finish());
+        assert_eq!(hasher3.finish(), hasher4.finish());
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_config() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let raw: Vec<u8> = (0..=255u8).cycle().take(1024).collect();
+        let mut u = Unstructured::new(&raw);
+        for _ in 0..4 {
+            let config = Xxh3Config::arbitrary(&mut u).unwrap();
+            let mut hasher = CustomXxh3Hasher::from_config(config);
+            hasher.write(TEST_DATA);
+
+            assert_eq!(hasher.seed(), config.seed);
+            assert_eq!(hasher.finish(), hasher.clone().finish());
+        }
+    }
+
+    #[test]
+    fn test_hash_empty() {
+        // pinned: changing XXH3_SECRET (or its seed) breaks stored digests
+        const EMPTY_DIGEST: u64 = 0x8082_2ED4_2944_43E6;
+
+        assert_eq!(hash_empty(), EMPTY_DIGEST);
+        assert_eq!(hash_bytes(b""), EMPTY_DIGEST);
+        assert_eq!(CustomXxh3Hasher::default().finish(), EMPTY_DIGEST);
+    }
+
+    #[test]
+    fn test_change_secret() {
+        let secret = [42u8; XXH3_SECRET_SIZE];
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.change_secret(&secret).unwrap();
+        hasher.write(TEST_DATA);
+
+        let mut expected = CustomXxh3Hasher::with_secret(&secret).unwrap();
+        expected.write(TEST_DATA);
+        assert_eq!(hasher.finish(), expected.finish());
+
+        assert!(matches!(
+            hasher.change_secret(&secret[..136]),
+            Err(Xxh3Error::SecretMismatch {
+                expected: XXH3_SECRET_SIZE,
+                actual: 136
+            })
+        ));
+        assert_eq!(
+            hasher.finish(),
+            expected.finish(),
+            "State should be untouched"
+        );
+    }
+
+    #[test]
+    fn test_shuffle_in_place() {
+        let seed = shuffle_seed(TEST_DATA);
+        let mut items1: Vec<u32> = (0..100).collect();
+        let mut items2: Vec<u32> = (0..100).collect();
+        let mut items3: Vec<u32> = (0..100).collect();
+
+        shuffle_in_place(&mut items1, seed);
+        shuffle_in_place(&mut items2, seed);
+        shuffle_in_place(&mut items3, seed ^ 1);
+
+        assert_eq!(items1, items2, "Same seed should give the same permutation");
+        assert_ne!(items1, items3);
+        assert_ne!(items1, (0..100).collect::<Vec<u32>>());
+
+        items1.sort_unstable();
+        assert_eq!(items1, (0..100).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn test_prefixed_hasher() {
+        let prefix = b"/api/v1/users/".repeat(20);
+        let prefixed = PrefixedHasher::new(&prefix);
+
+        for suffix in [&b""[..], b"1", b"12345", TEST_DATA] {
+            let full = [prefix.as_slice(), suffix].concat();
+            assert_eq!(prefixed.hash_suffix(suffix), hash_bytes(&full));
+        }
+        assert_eq!(prefixed.hash_suffix(b"1"), prefixed.hash_suffix(b"1"));
+    }
+
+    #[test]
+    fn test_hash_with_alt_seed() {
+        let mut hasher = CustomXxh3Hasher::new(1);
+        hasher.write(b"existing state");
+        let state = hasher.finish();
+
+        let mut expected = CustomXxh3Hasher::new(2);
+        expected.write(TEST_DATA);
+
+        assert_eq!(hasher.hash_with_alt_seed(TEST_DATA, 2), expected.finish());
+        assert_eq!(hasher.finish(), state, "State should be untouched");
+        assert_eq!(hasher.seed(), 1);
+
+        // a default hasher keeps XXH3_SECRET, which matters past 240 bytes
+        let long = [0xA5u8; 1024];
+        let default = CustomXxh3Hasher::default();
+        let mut expected = CustomXxh3Hasher::with_secret_and_seed(&XXH3_SECRET, 2).unwrap();
+        expected.write(&long);
+        assert_eq!(default.hash_with_alt_seed(&long, 2), expected.finish());
+
+        let mut plain = CustomXxh3Hasher::new(2);
+        plain.write(&long);
+        assert_ne!(default.hash_with_alt_seed(&long, 2), plain.finish());
+        assert_eq!(
+            CustomXxh3Hasher::new(0).hash_with_alt_seed(&long, 2),
+            plain.finish(),
+            "Hashers on Xxh3's default secret should keep it"
+        );
+
+        // change_seed() on a default hasher still rebuilds it with new()
+        let mut reseeded = CustomXxh3Hasher::default();
+        reseeded.change_seed(2);
+        reseeded.write(&long);
+        assert_eq!(reseeded.finish(), plain.finish());
+    }
+
+    #[test]
+    fn test_domain_digest() {
+        #[derive(Hash)]
+        struct Audio(u64);
+        #[derive(Hash)]
+        struct Video(u64);
+
+        impl HashDomain for Audio {
+            const DOMAIN_TAG: &'static [u8] = b"plugin.audio";
+        }
+        impl HashDomain for Video {
+            const DOMAIN_TAG: &'static [u8] = b"plugin.video";
+        }
+
+        assert_ne!(domain_digest(&Audio(42)), domain_digest(&Video(42)));
+        assert_eq!(domain_digest(&Audio(42)), domain_digest(&Audio(42)));
+        assert_ne!(domain_digest(&Audio(42)), hash_item(&42u64));
+    }
+
+    #[test]
+    fn test_collision_probability() {
+        assert_eq!(collision_probability(0, 64), 0.0);
+        assert_eq!(collision_probability(1, 64), 0.0);
+
+        // ~50% at roughly 5.06 billion 64-bit digests
+        let p = collision_probability(5_056_937_541, 64);
+        assert!((p - 0.5).abs() < 1e-6, "got {p}");
+
+        // 2^32 items: x = (2^64 - 2^32) / 2^65 ~ 0.5, p ~ 1 - e^-0.5
+        let p = collision_probability(1 << 32, 64);
+        assert!((p - 0.393_469_340).abs() < 1e-6, "got {p}");
+
+        let p = collision_probability(1 << 32, 128);
+        assert!(p > 0.0 && p < 1e-19, "got {p}");
+    }
+
+    #[test]
+    fn test_hash_option() {
+        let mut none = CustomXxh3Hasher::default();
+        let mut some = CustomXxh3Hasher::default();
+        none.hash_option::<u8>(&None);
+        some.hash_option(&Some(0u8));
+
+        assert_ne!(none.finish(), some.finish());
+        assert_eq!(none.finish(), hash_bytes(&[0]));
+        assert_eq!(some.finish(), hash_bytes(&[1, 0]));
+    }
+
+    #[test]
+    fn test_hash_result() {
+        let mut ok = CustomXxh3Hasher::default();
+        let mut err = CustomXxh3Hasher::default();
+        ok.hash_result::<u8, u8>(&Ok(0));
+        err.hash_result::<u8, u8>(&Err(0));
+
+        assert_ne!(ok.finish(), err.finish());
+        assert_eq!(ok.finish(), hash_bytes(&[0, 0]));
+        assert_eq!(err.finish(), hash_bytes(&[1, 0]));
+    }
+
+    #[cfg(feature = "hashbrown")]
+    #[test]
+    fn test_hashbrown_map() {
+        let mut map: Xxh3HashMap<String, usize> = xxh3_hashmap_with_capacity(16);
+        for i in 0..100 {
+            map.insert(format!("key-{i}"), i);
+        }
+        let mut set: Xxh3HashSet<u32> = xxh3_hashset_with_capacity(0);
+        set.extend(0..10);
+
+        assert_eq!(map.len(), 100);
+        assert_eq!(map.get("key-42"), Some(&42));
+        assert_eq!(map.get("missing"), None);
+        assert!(set.contains(&7));
+    }
+
+    #[test]
+    fn test_endianness_policy() {
+        let mut le = CustomXxh3Hasher::with_endianness(0, Endian::Little);
+        let mut be = CustomXxh3Hasher::with_endianness(0, Endian::Big);
+        let mut native = CustomXxh3Hasher::new(0);
+        le.write_u32(0x0102_0304);
+        be.write_u32(0x0102_0304);
+        native.write_u32(0x0102_0304);
+
+        assert_ne!(le.finish(), be.finish());
+        assert_eq!(le.finish(), xxh3_64(&[4, 3, 2, 1]));
+        assert_eq!(be.finish(), xxh3_64(&[1, 2, 3, 4]));
+        assert_eq!(native.finish(), xxh3_64(&0x0102_0304u32.to_ne_bytes()));
+
+        be.change_seed(0);
+        be.write_i16(-2);
+        assert_eq!(be.finish(), xxh3_64(&[0xFF, 0xFE]));
+    }
+
+    #[test]
+    fn test_fast_and_portable_presets() {
+        let digest = |mut hasher: CustomXxh3Hasher| {
+            hasher.write(TEST_DATA);
+            hasher.write_u64(0x0102_0304_0506_0708);
+            hasher.finish()
+        };
+
+        assert_eq!(
+            digest(CustomXxh3Hasher::fast()),
+            digest(CustomXxh3Hasher::fast())
+        );
+        assert_eq!(
+            digest(CustomXxh3Hasher::portable()),
+            digest(CustomXxh3Hasher::portable())
+        );
+        assert_ne!(
+            digest(CustomXxh3Hasher::fast()),
+            digest(CustomXxh3Hasher::portable())
+        );
+
+        let expected = [TEST_DATA, &[8, 7, 6, 5, 4, 3, 2, 1]].concat();
+        assert_eq!(digest(CustomXxh3Hasher::portable()), hash_bytes(&expected));
+    }
+
+    #[test]
+    fn test_checkpoints() {
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write(b"first entry;");
+        assert_eq!(hasher.since_checkpoint(), hasher.finish());
+
+        assert_eq!(hasher.checkpoint(), hash_bytes(b"first entry;"));
+        assert_eq!(hasher.since_checkpoint(), hash_empty());
+
+        hasher.write(b"second entry;");
+        assert_eq!(hasher.since_checkpoint(), hash_bytes(b"second entry;"));
+        assert_eq!(hasher.finish(), hash_bytes(b"first entry;second entry;"));
+
+        hasher.checkpoint();
+        hasher.write(b"third entry;");
+        assert_eq!(hasher.since_checkpoint(), hash_bytes(b"third entry;"));
+
+        hasher.reset();
+        hasher.write(TEST_DATA);
+        assert_eq!(hasher.since_checkpoint(), hash_bytes(TEST_DATA));
+    }
+
+    #[test]
+    fn test_secret_sanity() {
+        assert_eq!(XXH3_SECRET.len(), XXH3_SECRET_SIZE);
+        assert!(
+            XXH3_SECRET.iter().any(|&b| b != 0),
+            "Generated secret must not be all zeros"
+        );
+        assert_ne!(
+            XXH3_SECRET,
+            const_custom_default_secret(0),
+            "Generated secret must differ from the Xxh3 default"
+        );
+    }
+
+    #[test]
+    fn test_finish_nonzero() {
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write(TEST_DATA);
+        assert_eq!(hasher.finish_nonzero().get(), hasher.finish());
+
+        // a real zero digest is impractical to find, so force the remap
+        assert_eq!(nonzero_digest(0), NonZeroU64::MAX);
+        assert_eq!(nonzero_digest(1).get(), 1);
+        assert_eq!(nonzero_digest(u64::MAX), NonZeroU64::MAX);
+    }
+
+    #[test]
+    fn test_salted_hasher() {
+        let digest = |salt: u64| {
+            let mut hasher = SaltedXxh3Hasher::new(salt);
+            hasher.write(TEST_DATA);
+            hasher.write_u32(7);
+            hasher.finish()
+        };
+
+        assert_eq!(digest(1), digest(1), "Same salt should reproduce");
+        assert_ne!(digest(1), digest(2), "Different salts should diverge");
+
+        let mut unsalted = CustomXxh3Hasher::default();
+        unsalted.write(TEST_DATA);
+        unsalted.write_u32(7);
+        assert_eq!(digest(0), unsalted.finish(), "Zero salt is a no-op");
+    }
+
+    #[test]
+    fn test_const_str_hash() {
+        const TAG: u64 = const_str_hash!("literal");
+
+        assert_eq!(TAG, hash_bytes(b"literal"));
+        assert_eq!(const_str_hash!(""), hash_empty());
+
+        let route = |tag: u64| match tag {
+            t if t == const_str_hash!("/index") => 1,
+            t if t == const_str_hash!("/about") => 2,
+            _ => 0,
+        };
+        assert_eq!(route(hash_bytes(b"/about")), 2);
+        assert_eq!(route(hash_bytes(b"/missing")), 0);
+    }
+
+    #[test]
+    fn test_dedup_key() {
+        let data = b"Hello, world!";
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write(&data[..5]);
+        hasher.write(&data[5..]);
+
+        assert_eq!(dedup_key(data), dedup_key(b"Hello, world!"));
+        assert_eq!(hasher.dedup_key(), dedup_key(data));
+        assert_eq!(dedup_key(data).0, 13);
+
+        let (len1, _) = dedup_key(&data[..5]);
+        let (len2, _) = dedup_key(data);
+        assert_ne!(dedup_key(&data[..5]), dedup_key(data));
+        assert_ne!(len1, len2);
+    }
+
+    #[test]
+    fn test_output_transform() {
+        const DIGEST: u64 = 0x0123_4567_89AB_CDEF;
+
+        assert_eq!(OutputTransform::Identity.apply(DIGEST), DIGEST);
+        assert_eq!(
+            OutputTransform::ByteSwap.apply(DIGEST),
+            0xEFCD_AB89_6745_2301
+        );
+        assert_eq!(
+            OutputTransform::RotateLeft(8).apply(DIGEST),
+            0x2345_6789_ABCD_EF01
+        );
+        assert_eq!(OutputTransform::RotateLeft(64).apply(DIGEST), DIGEST);
+        assert_eq!(
+            OutputTransform::Mask(0xFFFF_FFFF).apply(DIGEST),
+            0x89AB_CDEF
+        );
+
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write(TEST_DATA);
+        let raw = hasher.finish();
+        assert_eq!(hasher.finish_transformed(OutputTransform::default()), raw);
+        assert_eq!(
+            hasher.finish_transformed(OutputTransform::ByteSwap),
+            raw.swap_bytes()
+        );
+    }
+
+    #[test]
+    fn test_versioned() {
+        let mut v1 = CustomXxh3Hasher::versioned(1, 0);
+        let mut v1_again = CustomXxh3Hasher::versioned(1, 0);
+        let mut v2 = CustomXxh3Hasher::versioned(2, 0);
+        v1.write(TEST_DATA);
+        v1_again.write(TEST_DATA);
+        v2.write(TEST_DATA);
+
+        assert_eq!(v1.finish(), v1_again.finish());
+        assert_ne!(v1.finish(), v2.finish());
+
+        v1.reset();
+        v1.write(TEST_DATA);
+        assert_eq!(
+            v1.finish(),
+            v1_again.finish(),
+            "Version should survive reset"
+        );
+    }
+
+    #[test]
+    fn test_progressive_hasher() {
+        let chunks: [&[u8]; 3] = [b"Hello", b", ", b"world!"];
+        let mut hasher = ProgressiveHasher::new();
+        let digests: Vec<u64> = chunks.iter().map(|c| hasher.write_chunk(c)).collect();
+
+        assert_eq!(digests[0], hash_bytes(b"Hello"));
+        assert_eq!(digests[1], hash_bytes(b"Hello, "));
+        assert_eq!(digests[2], hash_bytes(&chunks.concat()));
+        assert_eq!(hasher.into_inner().bytes_written(), 13);
+    }
+
+    #[test]
+    fn test_active_dispatch() {
+        let backend = active_dispatch();
+
+        assert!(!backend.is_empty());
+        assert!(["avx2", "sse2", "neon", "scalar"].contains(&backend));
+    }
+
+    #[test]
+    fn test_thread_local_seeded() {
+        let seed = CustomXxh3Hasher::thread_local_seeded().seed();
+        assert_eq!(seed, CustomXxh3Hasher::thread_local_seeded().seed());
+
+        let other = std::thread::spawn(|| CustomXxh3Hasher::thread_local_seeded().seed())
+            .join()
+            .unwrap();
+        assert_ne!(seed, other, "Threads should get different seeds");
+
+        let digest = |len: usize| {
+            let mut hasher = CustomXxh3Hasher::thread_local_seeded();
+            hasher.write(&vec![0xA5u8; len]);
+            hasher.finish()
+        };
+        for len in [16, 1024] {
+            let local: u64 = digest(len);
+            let other: u64 = std::thread::spawn(move || digest(len)).join().unwrap();
+            assert_eq!(local, digest(len));
+            assert_ne!(local, other, "Same digest across threads at {len} bytes");
+        }
+    }
+
+    #[test]
+    fn test_try_finish() {
+        let mut hasher = CustomXxh3Hasher::default();
+        assert!(matches!(hasher.try_finish(), Err(Xxh3Error::EmptyInput)));
+        assert_eq!(hasher.finish(), hash_empty());
+
+        hasher.write(TEST_DATA);
+        assert_eq!(hasher.try_finish().unwrap(), hash_bytes(TEST_DATA));
+
+        hasher.reset();
+        assert!(hasher.try_finish().is_err());
+    }
+
+    #[test]
+    fn test_hash_until_duplicate() {
+        let bufs: [&[u8]; 5] = [b"a", b"b", b"c", b"b", b"a"];
+        assert_eq!(hash_until_duplicate(&bufs), Some((1, 3)));
+
+        let distinct: [&[u8]; 3] = [b"a", b"b", b"c"];
+        assert_eq!(hash_until_duplicate(&distinct), None);
+        assert_eq!(hash_until_duplicate(&[]), None);
+    }
+
+    #[test]
+    fn test_record_hasher() {
+        let mut r1 = RecordHasher::new();
+        r1.field("id", b"42").field("name", b"foo");
+        let mut r2 = RecordHasher::new();
+        r2.field("name", b"foo").field("id", b"42");
+        let mut r3 = RecordHasher::new();
+        r3.field("id", b"42").field("title", b"foo");
+
+        assert_ne!(r1.finish(), r2.finish(), "Field order should matter");
+        assert_ne!(r1.finish(), r3.finish(), "Field names should matter");
+
+        let mut plain = CustomXxh3Hasher::default();
+        for part in [&b"id"[..], b"42", b"name", b"foo"] {
+            plain.write_framed(part);
+        }
+        assert_eq!(r1.finish(), plain.finish());
+    }
+
+    #[test]
+    fn test_rolling_combine32() {
+        let mut combine = RollingCombine32::new();
+        assert_eq!(combine.combined(), 1);
+
+        // a: 1 -> 2 -> 4 -> 9, b: 0 -> 2 -> 6 -> 15
+        combine.add(1);
+        combine.add(2);
+        combine.add(65521 + 5);
+        assert_eq!(combine.combined(), (15 << 16) | 9);
+
+        let mut swapped = RollingCombine32::default();
+        swapped.add(2);
+        swapped.add(1);
+        assert_ne!(swapped.combined(), (6 << 16) | 4);
+        assert_eq!(swapped.combined(), (7 << 16) | 4);
+    }
+
+    #[test]
+    fn test_write_reversed() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        let reversed: Vec<u8> = data.iter().rev().copied().collect();
+
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write_reversed(&data);
+        assert_eq!(hasher.finish(), hash_bytes(&reversed));
+        assert_eq!(hasher.bytes_written(), 1000);
+
+        let mut empty = CustomXxh3Hasher::default();
+        empty.write_reversed(&[]);
+        assert_eq!(empty.finish(), hash_empty());
+    }
+
+    #[test]
+    fn test_rehash() {
+        let digest = hash_bytes(TEST_DATA);
+        let shard1 = const_custom_default_secret(1);
+        let shard2 = const_custom_default_secret(2);
+
+        assert_ne!(
+            rehash(digest, &shard1).unwrap(),
+            rehash(digest, &shard2).unwrap()
+        );
+        assert_eq!(
+            rehash(digest, &XXH3_SECRET).unwrap(),
+            hash_bytes(&digest.to_le_bytes())
+        );
+        assert!(matches!(
+            rehash(digest, &[0u8; 10]),
+            Err(Xxh3Error::InvalidSecretSize(10))
+        ));
+
+        let digest128 = hash_bytes_128(TEST_DATA);
+        assert_ne!(
+            rehash_128(digest128, &shard1).unwrap(),
+            rehash_128(digest128, &shard2).unwrap()
+        );
+        assert!(rehash_128(digest128, &[]).is_err());
+    }
+
+    #[test]
+    fn test_hash_item_stack() {
+        assert_eq!(hash_item_stack(&42u64), hash_item(&42u64));
+        assert_eq!(hash_item_stack(&"foo"), hash_item(&"foo"));
+        assert_eq!(
+            hash_item_stack(&(1u8, [2u16; 4])),
+            hash_item(&(1u8, [2u16; 4]))
+        );
+        assert_eq!(
+            hash_item_stack(&(7usize, -3i128, 'x', String::from("bar"))),
+            hash_item(&(7usize, -3i128, 'x', String::from("bar")))
+        );
+    }
+
+    #[test]
+    fn test_finish_finalized() {
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write(TEST_DATA);
+        let before = hasher.finish();
+
+        assert_ne!(hasher.finish_finalized(1), hasher.finish_finalized(2));
+        assert_eq!(hasher.finish_finalized(1), hasher.finish_finalized(1));
+        assert_eq!(hasher.finish(), before, "State should be left untouched");
+
+        let tagged = hasher.finish_finalized(1);
+        hasher.write_u64(1);
+        assert_eq!(hasher.finish(), tagged);
+    }
+
+    #[test]
+    fn test_dyn_xxh3() {
+        let mut registry: Vec<Box<dyn DynHasher>> = vec![
+            DynXxh3::new().boxed(),
+            DynXxh3::with_hasher(CustomXxh3Hasher::new(1)).boxed(),
+        ];
+        for hasher in registry.iter_mut() {
+            hasher.update(TEST_DATA);
+        }
+
+        assert_eq!(registry[0].digest(), hash_bytes(TEST_DATA));
+        assert_ne!(registry[0].digest(), registry[1].digest());
+    }
+
+    #[test]
+    fn test_write_char() {
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write_char('A');
+        assert_eq!(hasher.finish(), hash_bytes(&[0x41, 0, 0, 0]));
+
+        assert_eq!(
+            hash_str_chars("Aé"),
+            hash_bytes(&[0x41, 0, 0, 0, 0xE9, 0, 0, 0])
+        );
+        assert_ne!(hash_str_chars("Aé"), hash_bytes("Aé".as_bytes()));
+    }
+
+    #[test]
+    fn test_derive_seed() {
+        let children: Vec<u64> = (0..100).map(|i| derive_seed(42, i)).collect();
+        let unique: std::collections::HashSet<&u64> = children.iter().collect();
+
+        assert_eq!(unique.len(), children.len(), "Children should be distinct");
+        assert_eq!(children[7], derive_seed(42, 7));
+        assert_ne!(derive_seed(42, 7), derive_seed(7, 42));
+        assert_ne!(derive_seed(42, 0), derive_seed(43, 0));
+    }
+
+    #[test]
+    fn test_partial_eq_digest() {
+        let mut hasher = CustomXxh3Hasher::default();
+        assert_eq!(hasher, hash_empty());
+
+        hasher.write(TEST_DATA);
+        assert_eq!(hasher, hash_bytes(TEST_DATA));
+        assert_ne!(hasher, hash_empty());
+    }
+
+    #[test]
+    fn test_impl_xxh3_hashable_macro() {
+        struct Record {
+            id: u64,
+            name: &'static str,
+            tags: Vec<u8>,
+        }
+        impl_xxh3_hashable!(Record { id, name, tags });
+
+        let r1 = Record {
+            id: 1,
+            name: "foo",
+            tags: vec![1, 2],
+        };
+        let r2 = Record {
+            id: 1,
+            name: "foo",
+            tags: vec![1, 2],
+        };
+        let r3 = Record {
+            id: 1,
+            name: "foo",
+            tags: vec![2, 1],
+        };
+
+        assert_eq!(r1.xxh3_digest(), r2.xxh3_digest());
+        assert_ne!(r1.xxh3_digest(), r3.xxh3_digest());
+        assert_eq!(
+            r1.xxh3_digest(),
+            collections::hash_tuple3(&(r1.id, r1.name, r1.tags.clone()))
+        );
+    }
+
+    #[test]
+    fn test_digest_fraction() {
+        for i in 0..1000u32 {
+            let frac = hash_bytes_fraction(&i.to_le_bytes());
+            assert!((0.0..1.0).contains(&frac));
+        }
+        assert_eq!(digest_fraction(0), 0.0);
+        assert!(digest_fraction(u64::MAX) < 1.0);
+
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write(TEST_DATA);
+        assert_eq!(hasher.finish_fraction(), hash_bytes_fraction(TEST_DATA));
+
+        let kept = (0..10_000u32)
+            .filter(|i| hash_bytes_fraction(&i.to_le_bytes()) < 0.1)
+            .count();
+        assert!(
+            (800..1200).contains(&kept),
+            "Expected ~10% sampled, got {kept}"
+        );
+    }
+
+    #[test]
+    fn test_write_run() {
+        let mut run = CustomXxh3Hasher::default();
+        run.write_run(0, 1000);
+        let mut again = CustomXxh3Hasher::default();
+        again.write_run(0, 1000);
+
+        assert_eq!(run.finish(), again.finish());
+        assert_eq!(run.bytes_written(), 9, "Runs should not be materialized");
+        assert_ne!(run.finish(), hash_bytes(&[0; 1000]));
+
+        let mut huge = CustomXxh3Hasher::default();
+        huge.write_run(0, u64::MAX);
+        assert_ne!(huge.finish(), run.finish());
+    }
+
+    #[test]
+    fn test_histogram_hasher() {
+        let mut hasher = HistogramHasher::new();
+        hasher.write(TEST_DATA);
+        hasher.write(&[0; 100]);
+
+        let histogram = hasher.byte_histogram();
+        assert_eq!(histogram.iter().sum::<u64>(), TEST_DATA.len() as u64 + 100);
+        assert_eq!(histogram[0], 100);
+        assert_eq!(histogram[b'l' as usize], 3);
+
+        let mut expected = TEST_DATA.to_vec();
+        expected.extend_from_slice(&[0; 100]);
+        assert_eq!(hasher.digest(), hash_bytes(&expected));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_colliding_builder() {
+        let builder = CollidingXxh3Builder::new(2);
+        let mut map: std::collections::HashMap<u32, u32, _> =
+            std::collections::HashMap::with_hasher(builder);
+        map.extend((0..100).map(|i| (i, i * 2)));
+
+        let digests: std::collections::HashSet<u64> =
+            (0..100u32).map(|i| builder.hash_one(i)).collect();
+        assert!(digests.len() <= 4, "Keys should collide into 4 digests");
+        assert!(digests.iter().all(|d| *d < 4));
+        for i in 0..100 {
+            assert_eq!(map[&i], i * 2);
+        }
+        assert_eq!(
+            CollidingXxh3Builder::new(64).hash_one(7u32),
+            hash_item(&7u32)
+        );
+    }
+
+    #[test]
+    fn test_finish_halves() {
+        let hasher = CustomXxh3Hasher::default();
+        assert_eq!(hasher.finish(), 0x8082_2ED4_2944_43E6);
+        assert_eq!(hasher.finish_halves(), (0x8082_2ED4, 0x2944_43E6));
+
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write(TEST_DATA);
+        let (high, low) = hasher.finish_halves();
+        assert_eq!((high as u64) << 32 | low as u64, hasher.finish());
+    }
+
+    #[test]
+    fn test_log_hasher() {
+        let log = |entries: &[&[u8]]| {
+            let mut hasher = LogHasher::new();
+            entries.iter().for_each(|e| hasher.append(e));
+            hasher
+        };
+        let original = log(&[b"start", b"work", b"stop"]);
+        let reordered = log(&[b"work", b"start", b"stop"]);
+        let changed = log(&[b"start", b"WORK", b"stop"]);
+
+        assert_ne!(original.content_digest(), reordered.content_digest());
+        assert_eq!(
+            original.order_independent_digest(),
+            reordered.order_independent_digest(),
+            "Reordering should only affect the content digest"
+        );
+        assert_ne!(original.content_digest(), changed.content_digest());
+        assert_ne!(
+            original.order_independent_digest(),
+            changed.order_independent_digest()
+        );
+    }
+
+    #[test]
+    fn test_from_secret_array() {
+        let secret = const_custom_default_secret(42);
+        let mut from_array = CustomXxh3Hasher::from_secret_array(secret);
+        let mut from_slice = CustomXxh3Hasher::with_secret(&secret).unwrap();
+        from_array.write(TEST_DATA);
+        from_slice.write(TEST_DATA);
+
+        assert_eq!(from_array.finish(), from_slice.finish());
+        assert_eq!(from_array.finish(), xxh3_64_with_secret(TEST_DATA, &secret));
+    }
+
+    #[test]
+    fn test_finish_usize() {
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write(TEST_DATA);
+
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(hasher.finish_usize() as u64, hasher.finish());
+        #[cfg(target_pointer_width = "32")]
+        {
+            let (high, low) = hasher.finish_halves();
+            assert_eq!(hasher.finish_usize(), (high ^ low) as usize);
+        }
+    }
+
+    #[test]
+    fn test_multi_digest() {
+        struct Entry {
+            id: u32,
+            body: Vec<u8>,
+        }
+        impl MultiDigest for Entry {
+            fn identity_digest(&self) -> u64 {
+                hash_item(&self.id)
+            }
+            fn content_digest(&self) -> u64 {
+                hash_bytes(&self.body)
+            }
+        }
+
+        let v1 = Entry {
+            id: 1,
+            body: b"old".to_vec(),
+        };
+        let v2 = Entry {
+            id: 1,
+            body: b"new".to_vec(),
+        };
+
+        assert_eq!(v1.identity_digest(), v2.identity_digest());
+        assert_ne!(v1.content_digest(), v2.content_digest());
+        assert_eq!(v1.digests(), (hash_item(&1u32), hash_bytes(b"old")));
+    }
+
+    #[test]
+    fn test_hash_bytes_mixed() {
+        let mean_distance = |f: fn(&[u8]) -> u64| {
+            let total: u32 = (0..255u8)
+                .map(|b| (f(&[b]) ^ f(&[b + 1])).count_ones())
+                .sum();
+            total as f64 / 255.0
+        };
+
+        assert_ne!(hash_bytes_mixed(b"a"), hash_bytes(b"a"));
+        assert_eq!(hash_bytes_mixed(b"a"), hash_bytes_mixed(b"a"));
+
+        // no measurable gain: both are already close to the ideal 32 bits
+        let mixed: f64 = mean_distance(hash_bytes_mixed);
+        let plain: f64 = mean_distance(hash_bytes);
+        assert!((30.0..34.0).contains(&mixed));
+        assert!((30.0..34.0).contains(&plain));
+        assert!(
+            (mixed - plain).abs() < 1.0,
+            "Mixed {mixed} vs plain {plain}"
+        );
+    }
+
+    #[test]
+    fn test_sample_digests() {
+        let data: Vec<[u8; 4]> = (0..10_000u32).map(|i| i.to_le_bytes()).collect();
+        let buffers: Vec<&[u8]> = data.iter().map(|b| b.as_slice()).collect();
+        let sample = sample_digests(&buffers, 0.25);
+
+        assert_eq!(sample, sample_digests(&buffers, 0.25));
+        assert!(
+            (2300..2700).contains(&sample.len()),
+            "Got {} samples",
+            sample.len()
+        );
+        assert!(sample_digests(&buffers, 0.0).is_empty());
+        assert_eq!(sample_digests(&buffers, 1.0).len(), buffers.len());
+    }
+
+    /// Known-answer tests for [feature_invariant_digest], covering every
+    /// XXH3 input size class. These must pass under any feature set.
+    #[test]
+    fn test_feature_invariant_digest_kat() {
+        let pattern = |n: usize| -> Vec<u8> { (0..n).map(|i| (i % 251) as u8).collect() };
+        let kats: [(usize, u64); 6] = [
+            (0, 0x8082_2ED4_2944_43E6),
+            (3, 0x5D8C_F474_2690_E32E),
+            (16, 0xEF36_DAED_31C9_7983),
+            (128, 0x35ED_EABE_94C0_182F),
+            (240, 0x4FF4_218C_1D52_D168),
+            (1024, 0xDF7E_4B9D_444E_45AC),
+        ];
+
+        for (len, expected) in kats {
+            assert_eq!(
+                feature_invariant_digest(&pattern(len)),
+                expected,
+                "KAT mismatch for {len} bytes"
+            );
+        }
+        assert_eq!(feature_invariant_digest(TEST_DATA), 0x9413_AAF7_2380_3555);
+    }
+
+    /// Re-run the [feature_invariant_digest] KATs in a child `cargo test`
+    /// per feature set: none, every optional feature alone, and all.
+    /// Keep the list in sync with the `[features]` table of Cargo.toml.
+    #[test]
+    fn test_feature_invariant_digest_matrix() {
+        const FEATURES: [&str; 8] = [
+            "arbitrary",
+            "bytemuck",
+            "cbor",
+            "crc32",
+            "hashbrown",
+            "size_of",
+            "testing",
+            "uuid",
+        ];
+
+        let mut feature_sets: Vec<Vec<&str>> = vec![vec!["--no-default-features"]];
+        for feature in FEATURES {
+            feature_sets.push(vec!["--no-default-features", "--features", feature]);
+        }
+        feature_sets.push(vec!["--all-features"]);
+
+        for flags in feature_sets {
+            let status = std::process::Command::new(env!("CARGO"))
+                .current_dir(env!("CARGO_MANIFEST_DIR"))
+                .args(["test", "--lib"])
+                .args(&flags)
+                .args(["--", "--exact", "tests::test_feature_invariant_digest_kat"])
+                .status()
+                .expect("Failed to run cargo");
+            assert!(status.success(), "KATs failed with {flags:?}");
+        }
+    }
+
+    #[test]
+    fn test_finish_with_length() {
+        let mut short = CustomXxh3Hasher::default();
+        short.write(&TEST_DATA[..5]);
+        let mut long = CustomXxh3Hasher::default();
+        long.write(TEST_DATA);
+
+        assert_ne!(short.finish_with_length(), long.finish_with_length());
+        assert_ne!(long.finish_with_length(), long.finish());
+        assert_eq!(
+            long.finish(),
+            hash_bytes(TEST_DATA),
+            "State should be untouched"
+        );
+
+        let mut manual = long.fork();
+        manual.write_framed(&13u64.to_le_bytes());
+        assert_eq!(long.finish_with_length(), manual.finish());
+    }
+
+    #[test]
+    fn test_quick_differ() {
+        let data = vec![0xA5u8; 4096];
+        let mut changed = data.clone();
+        changed[2048] ^= 1;
+
+        assert!(!quick_differ(&data, &data.clone()));
+        assert!(quick_differ(&data, &changed));
+        assert!(quick_differ(&data, &data[..4095]));
+        assert!(!quick_differ(b"", b""));
+    }
+
+    #[test]
+    fn test_finish_with_nonce() {
+        let mut hasher = CustomXxh3Hasher::default();
+        hasher.write(TEST_DATA);
+
+        assert_ne!(
+            hasher.finish_with_nonce(b"a"),
+            hasher.finish_with_nonce(b"b")
+        );
+        assert_ne!(hasher.finish_with_nonce(b""), hasher.finish());
+        assert_eq!(
+            hasher.finish_with_nonce(b"a"),
+            hasher.finish_with_nonce(b"a")
+        );
+        assert_eq!(
+            hasher.finish(),
+            hash_bytes(TEST_DATA),
+            "State should be untouched"
+        );
+    }
+
+    #[test]
+    fn test_hash_fixed_keys() {
+        let keys: Vec<[u8; 16]> = (0..1000u128).map(|i| i.to_le_bytes()).collect();
+        let naive: Vec<u64> = keys.iter().map(|k| hash_bytes(k)).collect();
+
+        assert_eq!(hash_fixed_keys(&keys), naive);
+        assert!(hash_fixed_keys::<16>(&[]).is_empty());
     }
 }
@@ -0,0 +1,134 @@
+// Copyright (c) 2024-2025 Mikko Tanner. All rights reserved.
+// License: MIT OR Apache-2.0
+
+use crate::{CustomXxh3Hasher, RandomXxh3Builder};
+use std::hash::{BuildHasher, DefaultHasher, Hasher, RandomState};
+
+/**
+A [BuildHasher] for migrating maps from the standard `SipHash` builder
+([RandomState]) to [RandomXxh3Builder].
+
+The mode is picked at runtime, but both modes share the same associated
+[MigrationHasher] type, so e.g. `HashMap<K, V, MigrationBuilder>` can be
+flipped between the two for A/B testing without touching type parameters
+at every call site.
+
+NOTE: the mode is fixed for the lifetime of a builder. A map built with
+one mode can't switch to the other, since all its hashes would change.
+
+Example:
+```ignore
+let mut map: HashMap<&str, u32, MigrationBuilder> =
+    HashMap::with_hasher(MigrationBuilder::new(config.use_xxh3));
+```
+*/
+#[derive(Clone)]
+pub enum MigrationBuilder {
+    SipHash(RandomState),
+    Xxh3(RandomXxh3Builder),
+}
+
+impl MigrationBuilder {
+    /// Create a new builder, using [RandomXxh3Builder] if `use_xxh3` is set
+    /// and the standard [RandomState] otherwise.
+    pub fn new(use_xxh3: bool) -> Self {
+        if use_xxh3 {
+            Self::xxh3()
+        } else {
+            Self::sip_hash()
+        }
+    }
+
+    /// Create a new builder using the standard `SipHash` [RandomState].
+    pub fn sip_hash() -> Self {
+        Self::SipHash(RandomState::new())
+    }
+
+    /// Create a new builder using [RandomXxh3Builder].
+    pub fn xxh3() -> Self {
+        Self::Xxh3(RandomXxh3Builder::new())
+    }
+
+    /// Is this builder using `xxHash3`?
+    pub fn is_xxh3(&self) -> bool {
+        matches!(self, Self::Xxh3(_))
+    }
+}
+
+impl Default for MigrationBuilder {
+    /// Defaults to `SipHash`, i.e. the behavior before migration.
+    fn default() -> Self {
+        Self::sip_hash()
+    }
+}
+
+impl BuildHasher for MigrationBuilder {
+    type Hasher = MigrationHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        match self {
+            Self::SipHash(state) => MigrationHasher::SipHash(state.build_hasher()),
+            Self::Xxh3(builder) => MigrationHasher::Xxh3(builder.build_hasher()),
+        }
+    }
+}
+
+/* --------------------------------- */
+
+/// The [Hasher] built by [MigrationBuilder], dispatching to either
+/// `SipHash` or [CustomXxh3Hasher].
+// Hashers are short-lived and built per lookup, so boxing isn't worth it.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+pub enum MigrationHasher {
+    SipHash(DefaultHasher),
+    Xxh3(CustomXxh3Hasher),
+}
+
+impl Hasher for MigrationHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        match self {
+            Self::SipHash(hasher) => hasher.write(bytes),
+            Self::Xxh3(hasher) => hasher.write(bytes),
+        }
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        match self {
+            Self::SipHash(hasher) => hasher.finish(),
+            Self::Xxh3(hasher) => hasher.finish(),
+        }
+    }
+}
+
+/* ######################################################################### */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn exercise_map(builder: MigrationBuilder) {
+        let mut map: HashMap<String, usize, MigrationBuilder> = HashMap::with_hasher(builder);
+        for i in 0..1000 {
+            map.insert(format!("key-{i}"), i);
+        }
+
+        assert_eq!(map.len(), 1000);
+        for i in 0..1000 {
+            assert_eq!(map.get(&format!("key-{i}")), Some(&i));
+        }
+        assert_eq!(map.get("missing"), None);
+    }
+
+    #[test]
+    fn test_both_modes_produce_working_maps() {
+        assert!(!MigrationBuilder::new(false).is_xxh3());
+        assert!(MigrationBuilder::new(true).is_xxh3());
+
+        exercise_map(MigrationBuilder::new(false));
+        exercise_map(MigrationBuilder::new(true));
+    }
+}
@@ -0,0 +1,370 @@
+// Copyright (c) 2024-2025 Mikko Tanner. All rights reserved.
+// License: MIT OR Apache-2.0
+
+use crate::{CustomXxh3Hasher, Xxh3Hashable};
+use std::{
+    hash::Hasher,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    path::{Component, Path},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Hash an [Xxh3Hashable] item with a default [CustomXxh3Hasher].
+#[inline]
+fn xxh3_digest_of<T: Xxh3Hashable + ?Sized>(item: &T) -> u64 {
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    item.xxh3(&mut hasher);
+    hasher.finish()
+}
+
+/// Hashes the whole seconds as a little-endian `u64`, followed by the
+/// sub-second nanoseconds as a little-endian `u32` (12 bytes in total).
+impl Xxh3Hashable for Duration {
+    #[inline]
+    fn xxh3<H: Hasher>(&self, state: &mut H) {
+        state.write(&self.as_secs().to_le_bytes());
+        state.write(&self.subsec_nanos().to_le_bytes());
+    }
+
+    fn xxh3_digest(&self) -> u64 {
+        xxh3_digest_of(self)
+    }
+}
+
+/// Hashes a sign byte (`0` for times at or after the UNIX epoch, `1` for
+/// times before it), followed by the [Duration] encoding of the distance
+/// from the epoch. Times before the epoch are thus handled gracefully.
+impl Xxh3Hashable for SystemTime {
+    fn xxh3<H: Hasher>(&self, state: &mut H) {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(after) => {
+                state.write(&[0]);
+                after.xxh3(state);
+            }
+            Err(before) => {
+                state.write(&[1]);
+                before.duration().xxh3(state);
+            }
+        }
+    }
+
+    fn xxh3_digest(&self) -> u64 {
+        xxh3_digest_of(self)
+    }
+}
+
+/// Hashes the 4 octets in network (big-endian) order.
+impl Xxh3Hashable for Ipv4Addr {
+    #[inline]
+    fn xxh3<H: Hasher>(&self, state: &mut H) {
+        state.write(&self.octets());
+    }
+
+    fn xxh3_digest(&self) -> u64 {
+        xxh3_digest_of(self)
+    }
+}
+
+/// Hashes the 16 octets in network (big-endian) order.
+impl Xxh3Hashable for Ipv6Addr {
+    #[inline]
+    fn xxh3<H: Hasher>(&self, state: &mut H) {
+        state.write(&self.octets());
+    }
+
+    fn xxh3_digest(&self) -> u64 {
+        xxh3_digest_of(self)
+    }
+}
+
+/// Hashes a family byte (`4` or `6`), followed by the address octets.
+///
+/// NOTE: addresses are not canonicalized, so an IPv4-mapped IPv6 address
+/// (`::ffff:a.b.c.d`) hashes differently from the plain IPv4 address.
+impl Xxh3Hashable for IpAddr {
+    fn xxh3<H: Hasher>(&self, state: &mut H) {
+        match self {
+            IpAddr::V4(ip) => {
+                state.write(&[4]);
+                ip.xxh3(state);
+            }
+            IpAddr::V6(ip) => {
+                state.write(&[6]);
+                ip.xxh3(state);
+            }
+        }
+    }
+
+    fn xxh3_digest(&self) -> u64 {
+        xxh3_digest_of(self)
+    }
+}
+
+/// Hashes the [IpAddr] encoding of the address, followed by the port as a
+/// little-endian `u16`. IPv6 flow info and scope id are *not* included.
+impl Xxh3Hashable for SocketAddr {
+    fn xxh3<H: Hasher>(&self, state: &mut H) {
+        self.ip().xxh3(state);
+        state.write(&self.port().to_le_bytes());
+    }
+
+    fn xxh3_digest(&self) -> u64 {
+        xxh3_digest_of(self)
+    }
+}
+
+/// Hashes the single byte as is, so byte arrays and slices hash like
+/// [write_framed](CustomXxh3Hasher::write_framed) of their contents.
+impl Xxh3Hashable for u8 {
+    #[inline]
+    fn xxh3<H: Hasher>(&self, state: &mut H) {
+        state.write_u8(*self);
+    }
+
+    fn xxh3_digest(&self) -> u64 {
+        xxh3_digest_of(self)
+    }
+}
+
+/// Hashes the length as a little-endian `u64`, followed by every element's
+/// [xxh3](Xxh3Hashable::xxh3) in order. The length framing keeps nested
+/// slices from bleeding into each other.
+impl<T: Xxh3Hashable> Xxh3Hashable for [T] {
+    fn xxh3<H: Hasher>(&self, state: &mut H) {
+        state.write(&(self.len() as u64).to_le_bytes());
+        for item in self {
+            item.xxh3(state);
+        }
+    }
+
+    fn xxh3_digest(&self) -> u64 {
+        xxh3_digest_of(self)
+    }
+}
+
+/// Hashes the same as the equivalent slice `[T]`.
+impl<T: Xxh3Hashable, const N: usize> Xxh3Hashable for [T; N] {
+    #[inline]
+    fn xxh3<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().xxh3(state);
+    }
+
+    fn xxh3_digest(&self) -> u64 {
+        xxh3_digest_of(self)
+    }
+}
+
+/// Implement [Xxh3Hashable] for tuples of [Xxh3Hashable] elements.
+macro_rules! xxh3_hashable_tuples {
+    ($($($idx:tt $t:ident),+;)+) => {$(
+        /// Hashes the [xxh3_digest](Xxh3Hashable::xxh3_digest) of every
+        /// element in order as a little-endian `u64`, so element boundaries
+        /// can't shift (the same framing as e.g. [hash_tuple2](crate::hash_tuple2)).
+        impl<$($t: Xxh3Hashable),+> Xxh3Hashable for ($($t,)+) {
+            fn xxh3<H: Hasher>(&self, state: &mut H) {
+                $(state.write(&self.$idx.xxh3_digest().to_le_bytes());)+
+            }
+
+            fn xxh3_digest(&self) -> u64 {
+                xxh3_digest_of(self)
+            }
+        }
+    )+};
+}
+
+xxh3_hashable_tuples! {
+    0 A, 1 B;
+    0 A, 1 B, 2 C;
+    0 A, 1 B, 2 C, 3 D;
+    0 A, 1 B, 2 C, 3 D, 4 E;
+    0 A, 1 B, 2 C, 3 D, 4 E, 5 F;
+    0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G;
+    0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 I;
+}
+
+/**
+Hash a [Path] portably, so the same logical path hashes equally on Windows
+and Unix (the std [Hash] of paths depends on the platform's `OsStr`).
+
+The path is split into its [Component]s, which normalizes separators,
+repeated separators and interior `.` components. Separators are those of
+the host platform, though: Windows accepts both `\` and `/`, but on Unix
+`\` is an ordinary character, so `a\b` is a single component there and
+doesn't hash like `a/b`. Spell paths with `/` to hash them equally on
+every platform. Every component is then
+written as a tag byte: `0` for a normal component followed by its UTF-8
+name framed with its length (little-endian `u64`), `1` for the root, `2`
+for a leading `.`, `3` for `..`, or `4` followed by the framed prefix (on
+Windows, e.g. `C:`). Non-UTF-8 names are converted lossily (invalid
+sequences become U+FFFD), so such paths may collide with each other.
+*/
+pub fn hash_path_portable(p: &Path) -> u64 {
+    let mut hasher: CustomXxh3Hasher = CustomXxh3Hasher::default();
+    for component in p.components() {
+        match component {
+            Component::Normal(name) => {
+                hasher.write(&[0]);
+                hasher.write_framed(name.to_string_lossy().as_bytes());
+            }
+            Component::RootDir => hasher.write(&[1]),
+            Component::CurDir => hasher.write(&[2]),
+            Component::ParentDir => hasher.write(&[3]),
+            Component::Prefix(prefix) => {
+                hasher.write(&[4]);
+                hasher.write_framed(prefix.as_os_str().to_string_lossy().as_bytes());
+            }
+        }
+    }
+    hasher.finish()
+}
+
+/* ######################################################################### */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash_bytes;
+
+    #[test]
+    fn test_duration_digest() {
+        let d1 = Duration::new(5, 250);
+        let d2 = Duration::from_nanos(5_000_000_250);
+
+        assert_eq!(d1.xxh3_digest(), d2.xxh3_digest());
+        assert_ne!(d1.xxh3_digest(), Duration::new(5, 251).xxh3_digest());
+        assert_eq!(
+            d1.xxh3_digest(),
+            hash_bytes(&[5, 0, 0, 0, 0, 0, 0, 0, 250, 0, 0, 0])
+        );
+    }
+
+    #[test]
+    fn test_system_time_digest() {
+        let offset = Duration::new(1_000, 0);
+        let after = UNIX_EPOCH + offset;
+        let before = UNIX_EPOCH - offset;
+
+        assert_eq!(after.xxh3_digest(), (UNIX_EPOCH + offset).xxh3_digest());
+        assert_ne!(after.xxh3_digest(), before.xxh3_digest());
+        assert_ne!(UNIX_EPOCH.xxh3_digest(), Duration::ZERO.xxh3_digest());
+    }
+
+    #[test]
+    fn test_ip_addr_digest() {
+        let v4: IpAddr = "192.168.1.1".parse().unwrap();
+        let mapped: IpAddr = "::ffff:192.168.1.1".parse().unwrap();
+
+        assert_eq!(
+            v4.xxh3_digest(),
+            IpAddr::from([192, 168, 1, 1]).xxh3_digest()
+        );
+        assert_eq!(v4.xxh3_digest(), hash_bytes(&[4, 192, 168, 1, 1]));
+        assert_eq!(
+            Ipv4Addr::new(192, 168, 1, 1).xxh3_digest(),
+            hash_bytes(&[192, 168, 1, 1])
+        );
+        assert_ne!(v4.xxh3_digest(), mapped.xxh3_digest());
+    }
+
+    #[test]
+    fn test_socket_addr_digest() {
+        let addr: SocketAddr = "10.0.0.1:8080".parse().unwrap();
+
+        assert_eq!(
+            addr.xxh3_digest(),
+            "10.0.0.1:8080".parse::<SocketAddr>().unwrap().xxh3_digest()
+        );
+        assert_ne!(
+            addr.xxh3_digest(),
+            "10.0.0.1:8081".parse::<SocketAddr>().unwrap().xxh3_digest()
+        );
+        assert_eq!(
+            addr.xxh3_digest(),
+            hash_bytes(&[4, 10, 0, 0, 1, 0x90, 0x1F])
+        );
+    }
+
+    #[test]
+    fn test_hash_path_portable() {
+        let built: std::path::PathBuf = ["/", "usr", "lib", "file.txt"].iter().collect();
+        let digest = hash_path_portable(Path::new("/usr/lib/file.txt"));
+
+        assert_eq!(hash_path_portable(&built), digest);
+        assert_eq!(
+            hash_path_portable(Path::new("/usr//lib/./file.txt/")),
+            digest
+        );
+        assert_ne!(hash_path_portable(Path::new("usr/lib/file.txt")), digest);
+        assert_ne!(
+            hash_path_portable(Path::new("/usr/lib/../file.txt")),
+            digest
+        );
+        assert_ne!(hash_path_portable(Path::new("/usr/libfile.txt")), digest);
+
+        #[cfg(windows)]
+        {
+            assert_eq!(hash_path_portable(Path::new(r"\usr\lib\file.txt")), digest);
+            assert_eq!(
+                hash_path_portable(Path::new(r"usr\lib/file.txt")),
+                hash_path_portable(Path::new("usr/lib/file.txt"))
+            );
+        }
+        #[cfg(unix)]
+        assert_ne!(
+            hash_path_portable(Path::new(r"usr\lib")),
+            hash_path_portable(Path::new("usr/lib")),
+            "Backslashes aren't separators on Unix"
+        );
+    }
+
+    #[test]
+    fn test_tuple_digest() {
+        let a = Duration::new(1, 0);
+        let b = Duration::new(2, 0);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        assert_eq!((a, b).xxh3_digest(), (a, b).xxh3_digest());
+        assert_ne!((a, b).xxh3_digest(), (b, a).xxh3_digest());
+
+        let mut framed = Vec::new();
+        framed.extend_from_slice(&a.xxh3_digest().to_le_bytes());
+        framed.extend_from_slice(&b.xxh3_digest().to_le_bytes());
+        assert_eq!((a, b).xxh3_digest(), hash_bytes(&framed));
+
+        let mut raw = CustomXxh3Hasher::default();
+        a.xxh3(&mut raw);
+        b.xxh3(&mut raw);
+        assert_ne!(
+            (a, b).xxh3_digest(),
+            raw.finish(),
+            "Elements should be framed"
+        );
+
+        let quad = (a, ip, b, UNIX_EPOCH);
+        assert_ne!(quad.xxh3_digest(), (a, b, ip, UNIX_EPOCH).xxh3_digest());
+        assert_ne!(quad.xxh3_digest(), (a, ip, b, UNIX_EPOCH + a).xxh3_digest());
+    }
+
+    #[test]
+    fn test_slice_digest() {
+        let bytes: [u8; 4] = [1, 2, 3, 4];
+        let mut framed = CustomXxh3Hasher::default();
+        framed.write_framed(&bytes);
+
+        assert_eq!(bytes.xxh3_digest(), framed.finish());
+        assert_eq!(bytes.xxh3_digest(), bytes[..].xxh3_digest());
+
+        let addrs: [Ipv4Addr; 2] = [Ipv4Addr::new(1, 2, 3, 4), Ipv4Addr::new(5, 6, 7, 8)];
+        let mut framed = CustomXxh3Hasher::default();
+        framed.write(&2u64.to_le_bytes());
+        framed.write(&[1, 2, 3, 4, 5, 6, 7, 8]);
+
+        assert_eq!(addrs.xxh3_digest(), framed.finish());
+        assert_eq!(addrs.xxh3_digest(), addrs[..].xxh3_digest());
+
+        let (a, b) = (Duration::new(1, 0), Duration::new(2, 0));
+        let nested: [[Duration; 2]; 2] = [[a, b], [a, b]];
+        let flat: [Duration; 4] = [a, b, a, b];
+        assert_ne!(nested.xxh3_digest(), flat.xxh3_digest());
+    }
+}
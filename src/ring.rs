@@ -0,0 +1,136 @@
+// Copyright (c) 2024-2025 Mikko Tanner. All rights reserved.
+// License: MIT OR Apache-2.0
+
+use crate::hash_bytes;
+use std::collections::BTreeMap;
+
+/// Default number of virtual nodes placed on the ring per real node.
+pub const DEFAULT_VIRTUAL_NODES: usize = 100;
+
+/**
+A consistent-hashing ring for distributing keys across nodes.
+
+Every node is placed on a 64-bit ring at `virtual_nodes` positions, each
+being the [hash_bytes] digest of the node id bytes followed by the replica
+index as a little-endian `u32`. A key is resolved to the first node found
+clockwise from the [hash_bytes] digest of the key (wrapping around).
+
+More virtual nodes give a more even key distribution at the cost of memory
+([DEFAULT_VIRTUAL_NODES] is a reasonable default). Adding or removing a node
+only remaps the keys which land on (or used to land on) that node.
+*/
+#[derive(Clone, Debug)]
+pub struct ConsistentHashRing<N> {
+    virtual_nodes: usize,
+    ring: BTreeMap<u64, N>,
+}
+
+impl<N: AsRef<[u8]> + Clone + PartialEq> ConsistentHashRing<N> {
+    /// Create a new, empty ring placing `virtual_nodes` positions per node.
+    pub fn new(virtual_nodes: usize) -> Self {
+        Self {
+            virtual_nodes,
+            ring: BTreeMap::new(),
+        }
+    }
+
+    /// Ring position of the given virtual node (replica) of a node.
+    fn position(node: &N, replica: usize) -> u64 {
+        let id: &[u8] = node.as_ref();
+        let mut buf: Vec<u8> = Vec::with_capacity(id.len() + 4);
+        buf.extend_from_slice(id);
+        buf.extend_from_slice(&(replica as u32).to_le_bytes());
+        hash_bytes(&buf)
+    }
+
+    /// Add a node to the ring. Adding an already present node is a no-op.
+    pub fn add_node(&mut self, node: N) {
+        for replica in 0..self.virtual_nodes {
+            self.ring
+                .entry(Self::position(&node, replica))
+                .or_insert_with(|| node.clone());
+        }
+    }
+
+    /// Remove a node (all of its virtual nodes) from the ring.
+    pub fn remove_node(&mut self, node: &N) {
+        self.ring.retain(|_, n| n != node);
+    }
+
+    /// Resolve a key to its node, or `None` if the ring is empty.
+    pub fn lookup(&self, key: &[u8]) -> Option<&N> {
+        let digest: u64 = hash_bytes(key);
+        self.ring
+            .range(digest..)
+            .next()
+            .or_else(|| self.ring.iter().next())
+            .map(|(_, node)| node)
+    }
+
+    /// Get the number of virtual nodes on the ring.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Check whether the ring has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+}
+
+impl<N: AsRef<[u8]> + Clone + PartialEq> Default for ConsistentHashRing<N> {
+    fn default() -> Self {
+        Self::new(DEFAULT_VIRTUAL_NODES)
+    }
+}
+
+/* ######################################################################### */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> Vec<Vec<u8>> {
+        (0..1000u32).map(|i| i.to_le_bytes().to_vec()).collect()
+    }
+
+    fn ring_of(nodes: &[&'static str]) -> ConsistentHashRing<&'static str> {
+        let mut ring = ConsistentHashRing::default();
+        for node in nodes {
+            ring.add_node(*node);
+        }
+        ring
+    }
+
+    #[test]
+    fn test_ring_deterministic() {
+        let ring1 = ring_of(&["a", "b", "c"]);
+        let ring2 = ring_of(&["c", "a", "b"]);
+
+        assert_eq!(ring1.len(), 3 * DEFAULT_VIRTUAL_NODES);
+        for key in keys() {
+            assert_eq!(ring1.lookup(&key), ring2.lookup(&key));
+        }
+        assert!(ConsistentHashRing::<&str>::default().lookup(b"x").is_none());
+    }
+
+    #[test]
+    fn test_ring_remove_only_remaps_own_keys() {
+        let full = ring_of(&["a", "b", "c"]);
+        let mut reduced = full.clone();
+        reduced.remove_node(&"b");
+
+        let mut moved = 0;
+        for key in keys() {
+            let before = *full.lookup(&key).unwrap();
+            let after = *reduced.lookup(&key).unwrap();
+            if before == "b" {
+                assert_ne!(after, "b");
+                moved += 1;
+            } else {
+                assert_eq!(before, after, "Keys of other nodes should stay put");
+            }
+        }
+        assert!(moved > 0);
+    }
+}
@@ -0,0 +1,286 @@
+// Copyright (c) 2024-2025 Mikko Tanner. All rights reserved.
+// License: MIT OR Apache-2.0
+
+use crate::{hash_bytes, CustomXxh3Hasher};
+use std::hash::{Hash, Hasher};
+
+/**
+Compute the [hash_bytes] digest of every sliding window of `window` bytes
+(n-grams), in order. This is a building block for min-hash / winnowing
+style similarity sketches.
+
+Returns an empty vector if `window` is zero or larger than `bytes`.
+*/
+pub fn window_hashes(bytes: &[u8], window: usize) -> Vec<u64> {
+    if window == 0 || window > bytes.len() {
+        return Vec::new();
+    }
+    bytes.windows(window).map(hash_bytes).collect()
+}
+
+/**
+A min-hash sketch of a set of items, for estimating the Jaccard similarity
+of two sets.
+
+Every item is hashed with `k` differently seeded [CustomXxh3Hasher]s (seeds
+`0..k`, each with a secret derived from its seed so that the hash functions
+stay independent for items longer than 240 bytes), and the minimum digest
+per seed is kept as the signature. Two sketches must use the same `k` to be
+comparable.
+*/
+#[derive(Clone, Debug)]
+pub struct MinHashSketch {
+    hashers: Vec<CustomXxh3Hasher>,
+    mins: Vec<u64>,
+}
+
+impl MinHashSketch {
+    /// Create a new, empty sketch with `k` hash functions.
+    pub fn new(k: usize) -> Self {
+        Self {
+            hashers: (0..k as u64)
+                .map(CustomXxh3Hasher::with_derived_secret)
+                .collect(),
+            mins: vec![u64::MAX; k],
+        }
+    }
+
+    /// Add an item to the sketch.
+    pub fn add<T: Hash>(&mut self, item: &T) {
+        for (hasher, min) in self.hashers.iter().zip(self.mins.iter_mut()) {
+            let mut hasher: CustomXxh3Hasher = hasher.clone();
+            item.hash(&mut hasher);
+            *min = (*min).min(hasher.finish());
+        }
+    }
+
+    /// Get the signature: the minimum digest per hash function.
+    pub fn signature(&self) -> Vec<u64> {
+        self.mins.clone()
+    }
+
+    /// Estimate the Jaccard similarity with another sketch as the fraction
+    /// of matching signature slots.
+    pub fn jaccard(&self, other: &Self) -> f64 {
+        let k: usize = self.mins.len().max(other.mins.len());
+        if k == 0 {
+            return 0.0;
+        }
+        let matching: usize = self
+            .mins
+            .iter()
+            .zip(&other.mins)
+            .filter(|(a, b)| a == b)
+            .count();
+        matching as f64 / k as f64
+    }
+}
+
+/* --------------------------------- */
+
+/**
+A SimHash accumulator for near-duplicate detection.
+
+Every feature is hashed with [hash_bytes], and each of the 64 digest bits
+votes +1 (set) or -1 (unset) on the corresponding fingerprint bit. The
+Hamming distance between two fingerprints estimates how similar the
+underlying feature sets are.
+*/
+#[derive(Clone, Debug)]
+pub struct SimHash {
+    votes: [i64; 64],
+}
+
+impl SimHash {
+    /// Create a new, empty accumulator.
+    pub fn new() -> Self {
+        Self { votes: [0; 64] }
+    }
+
+    /// Add a feature (e.g. a word or shingle) to the accumulator.
+    pub fn add_feature(&mut self, feature: &[u8]) {
+        let digest: u64 = hash_bytes(feature);
+        for (bit, vote) in self.votes.iter_mut().enumerate() {
+            if digest & (1 << bit) != 0 {
+                *vote += 1;
+            } else {
+                *vote -= 1;
+            }
+        }
+    }
+
+    /// Get the 64-bit fingerprint: bits with a positive vote total are set.
+    pub fn fingerprint(&self) -> u64 {
+        self.votes
+            .iter()
+            .enumerate()
+            .filter(|(_, vote)| **vote > 0)
+            .fold(0, |acc, (bit, _)| acc | (1 << bit))
+    }
+
+    /// Get the Hamming distance between the fingerprints of two accumulators.
+    pub fn distance(&self, other: &Self) -> u32 {
+        (self.fingerprint() ^ other.fingerprint()).count_ones()
+    }
+}
+
+impl Default for SimHash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/* --------------------------------- */
+
+/**
+A deterministic, order-independent sampling reservoir: keeps the `k` items
+with the smallest keys seen, where an item's key is the [hash_bytes] digest
+of the bytes identifying it (e.g. a record id).
+
+Since the keys don't depend on the order items are offered in, the same set
+of items always yields the same sample. Offers with a key already in the
+sample are ignored, so the identifying bytes should be unique per item.
+*/
+#[derive(Clone, Debug)]
+pub struct HashReservoir<T> {
+    k: usize,
+    keys: Vec<u64>,
+    items: Vec<T>,
+}
+
+impl<T> HashReservoir<T> {
+    /// Create a new, empty reservoir keeping at most `k` items.
+    pub fn new(k: usize) -> Self {
+        Self {
+            k,
+            keys: Vec::with_capacity(k),
+            items: Vec::with_capacity(k),
+        }
+    }
+
+    /// Offer an item identified by `bytes` to the reservoir.
+    pub fn offer(&mut self, item: T, bytes: &[u8]) {
+        let key: u64 = hash_bytes(bytes);
+        let pos: usize = self.keys.partition_point(|k| *k < key);
+        if pos >= self.k || self.keys.get(pos) == Some(&key) {
+            return;
+        }
+        self.keys.insert(pos, key);
+        self.items.insert(pos, item);
+        self.keys.truncate(self.k);
+        self.items.truncate(self.k);
+    }
+
+    /// Get the sampled items, ordered by ascending key.
+    pub fn sample(&self) -> &[T] {
+        &self.items
+    }
+}
+
+/* ######################################################################### */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_DATA: &[u8] = b"Hello, world!";
+
+    #[test]
+    fn test_window_hashes() {
+        let hashes = window_hashes(TEST_DATA, 5);
+
+        assert_eq!(hashes.len(), TEST_DATA.len() - 4);
+        assert_eq!(hashes[0], hash_bytes(b"Hello"));
+        assert_eq!(hashes[7], hash_bytes(b"world"));
+        assert_eq!(window_hashes(TEST_DATA, 13), vec![hash_bytes(TEST_DATA)]);
+        assert!(window_hashes(TEST_DATA, 14).is_empty());
+        assert!(window_hashes(TEST_DATA, 0).is_empty());
+    }
+
+    #[test]
+    fn test_min_hash_sketch() {
+        let mut sketch1 = MinHashSketch::new(64);
+        let mut sketch2 = MinHashSketch::new(64);
+        let mut sketch3 = MinHashSketch::new(64);
+        for i in 0..100u32 {
+            sketch1.add(&i);
+        }
+        for i in (0..100u32).rev() {
+            sketch2.add(&i);
+        }
+        for i in 1000..1100u32 {
+            sketch3.add(&i);
+        }
+
+        assert_eq!(sketch1.signature().len(), 64);
+        assert_eq!(sketch1.signature(), sketch2.signature());
+        assert_ne!(sketch1.signature(), sketch3.signature());
+        assert_eq!(sketch1.jaccard(&sketch2), 1.0);
+        assert!(sketch1.jaccard(&sketch3) < 0.1);
+    }
+
+    #[test]
+    fn test_min_hash_sketch_long_items() {
+        // past 240 bytes Xxh3 ignores the seed, so the secret must differ too
+        let item = |i: u32| -> Vec<u8> { i.to_le_bytes().repeat(75) };
+        let mut sketch1 = MinHashSketch::new(128);
+        let mut sketch2 = MinHashSketch::new(128);
+        for i in 0..100 {
+            sketch1.add(&item(i));
+        }
+        for i in 50..150 {
+            sketch2.add(&item(i));
+        }
+
+        let signature: Vec<u64> = sketch1.signature();
+        assert!(signature.iter().any(|&min| min != signature[0]));
+        let jaccard: f64 = sketch1.jaccard(&sketch2);
+        assert!(
+            (0.2..0.47).contains(&jaccard),
+            "Estimate {jaccard} too far from 1/3"
+        );
+    }
+
+    #[test]
+    fn test_sim_hash() {
+        let mut base = SimHash::new();
+        let mut similar = SimHash::new();
+        let mut disjoint = SimHash::new();
+        for i in 0..100 {
+            base.add_feature(format!("word-{i}").as_bytes());
+            similar.add_feature(format!("word-{}", if i < 95 { i } else { i + 500 }).as_bytes());
+            disjoint.add_feature(format!("other-{i}").as_bytes());
+        }
+
+        assert_eq!(base.distance(&base.clone()), 0);
+        assert!(base.distance(&similar) < 12);
+        assert!(base.distance(&disjoint) > 20);
+    }
+
+    #[test]
+    fn test_hash_reservoir() {
+        let mut forward = HashReservoir::new(10);
+        let mut backward = HashReservoir::new(10);
+        for i in 0..1000u32 {
+            forward.offer(i, &i.to_le_bytes());
+        }
+        for i in (0..1000u32).rev() {
+            backward.offer(i, &i.to_le_bytes());
+        }
+
+        assert_eq!(forward.sample().len(), 10);
+        assert_eq!(forward.sample(), backward.sample());
+
+        let mut expected: Vec<u32> = (0..1000).collect();
+        expected.sort_by_key(|i| hash_bytes(&i.to_le_bytes()));
+        assert_eq!(forward.sample(), &expected[..10]);
+
+        forward.offer(expected[0], &expected[0].to_le_bytes());
+        assert_eq!(
+            forward.sample(),
+            &expected[..10],
+            "Duplicates should be ignored"
+        );
+        assert!(HashReservoir::<u32>::new(0).sample().is_empty());
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright (c) 2024-2025 Mikko Tanner. All rights reserved.
+// License: MIT OR Apache-2.0
+
+//! Checks that hashing small items with a default hasher never allocates,
+//! using a global allocator which counts allocations per thread.
+
+use custom_xxh3::{hash_item, hash_item_stack};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Count the allocations made by `f` on the current thread.
+fn allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before: usize = ALLOCATIONS.with(Cell::get);
+    let result: R = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn test_hash_item_no_alloc() {
+    let item = (42u64, "foo", [1u16; 4], 'x');
+
+    let (stack_digest, stack_allocs) = allocations(|| hash_item_stack(&item));
+    let (item_digest, item_allocs) = allocations(|| hash_item(&item));
+
+    assert_eq!(stack_digest, item_digest);
+    assert_eq!(stack_allocs, 0, "hash_item_stack() allocated");
+    assert_eq!(item_allocs, 0, "hash_item() allocated");
+
+    let (_, vec_allocs) = allocations(|| vec![0u8; 16]);
+    assert_eq!(vec_allocs, 1, "Allocations should be counted");
+}